@@ -1,29 +1,35 @@
-use std::{fmt, time::Duration};
+use std::{fmt, sync::Arc, time::Duration};
 
 use mpd_protocol::{
-    command::{Command as RawCommand, CommandList as RawCommandList},
-    response::Response,
+    command::Command as RawCommand,
+    response::{Frame, Response},
     AsyncConnection, MpdProtocolError,
 };
 use tokio::{
     io::{AsyncRead, AsyncWrite},
     sync::mpsc::{UnboundedReceiver, UnboundedSender},
-    time::timeout,
+    time::{sleep, timeout, Instant},
 };
-use tracing::{debug, error, span, trace, Instrument, Level};
+use tracing::{debug, error, span, trace, warn, Instrument, Level};
 
-use crate::client::{CommandResponder, ConnectionError, ConnectionEvent, Subsystem};
+use crate::client::{
+    CommandRequest, CommandResponder, ConnectionError, ConnectionEvent, Subsystem,
+};
 
 struct State<C> {
     loop_state: LoopState,
     connection: AsyncConnection<C>,
-    commands: UnboundedReceiver<(RawCommandList, CommandResponder)>,
+    commands: UnboundedReceiver<CommandRequest>,
     events: UnboundedSender<ConnectionEvent>,
+    /// Partition the connection was switched to, if any.
+    partition: Option<Arc<str>>,
 }
 
 enum LoopState {
     Idling,
-    WaitingForCommandReply(CommandResponder),
+    /// The partition is set if the command switches to it when successful. The instant is when
+    /// the command was sent, for measuring the round-trip time.
+    WaitingForCommandReply(CommandResponder, Option<Arc<str>>, Instant),
 }
 
 impl fmt::Debug for LoopState {
@@ -31,7 +37,7 @@ impl fmt::Debug for LoopState {
         // avoid Debug-printing the noisy internals of the contained channel type
         match self {
             LoopState::Idling => write!(f, "Idling"),
-            LoopState::WaitingForCommandReply(_) => write!(f, "WaitingForCommandReply"),
+            LoopState::WaitingForCommandReply(..) => write!(f, "WaitingForCommandReply"),
         }
     }
 }
@@ -46,7 +52,7 @@ fn cancel_idle() -> RawCommand {
 
 pub(super) async fn run_loop<C>(
     mut connection: AsyncConnection<C>,
-    commands: UnboundedReceiver<(RawCommandList, CommandResponder)>,
+    commands: UnboundedReceiver<CommandRequest>,
     events: UnboundedSender<ConnectionEvent>,
 ) where
     C: AsyncRead + AsyncWrite + Unpin,
@@ -63,6 +69,7 @@ pub(super) async fn run_loop<C>(
         connection,
         commands,
         events,
+        partition: None,
     };
 
     trace!("entering run loop");
@@ -79,9 +86,89 @@ pub(super) async fn run_loop<C>(
     trace!("exited run_loop");
 }
 
+/// Loop used by connections which only listen for state changes and never send commands.
+pub(super) async fn run_idle_loop<C>(
+    mut connection: AsyncConnection<C>,
+    events: UnboundedSender<ConnectionEvent>,
+) where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    trace!("entering idle loop");
+
+    loop {
+        if let Err(e) = connection.send(idle()).await {
+            error!(error = ?e, "failed to send idle command");
+            let _ = events.send(ConnectionEvent::ConnectionClosed(e.into()));
+            break;
+        }
+
+        let response = tokio::select! {
+            response = connection.receive() => response,
+            _ = events.closed() => {
+                trace!("event receiver dropped");
+                break;
+            }
+        };
+
+        match response {
+            Ok(Some(res)) => match res.into_single_frame() {
+                Ok(f) => {
+                    emit_idle_events(&events, f, None);
+                }
+                Err(e) => {
+                    error!(code = e.code, message = e.message, "idle returned an error");
+                    let _ = events.send(ConnectionEvent::ConnectionClosed(
+                        ConnectionError::InvalidResponse,
+                    ));
+                    break;
+                }
+            },
+            Ok(None) => break, // The connection was closed
+            Err(e) => {
+                error!(error = ?e, "state change error");
+                let _ = events.send(ConnectionEvent::ConnectionClosed(e.into()));
+                break;
+            }
+        }
+    }
+
+    trace!("exited idle loop");
+}
+
+/// Emit the events contained in a response to an `idle` (or `noidle`) command, tagged with the
+/// current partition.
+fn emit_idle_events(
+    events: &UnboundedSender<ConnectionEvent>,
+    frame: Frame,
+    partition: Option<&Arc<str>>,
+) {
+    let mut unexpected = false;
+
+    for (key, value) in frame.fields() {
+        if key == "changed" {
+            let subsystem = Subsystem::from_raw(value);
+            debug!(?subsystem, "state change");
+            let _ = events.send(ConnectionEvent::SubsystemChange(
+                subsystem,
+                partition.cloned(),
+            ));
+        } else {
+            unexpected = true;
+        }
+    }
+
+    if unexpected {
+        warn!(?frame, "unexpected fields in idle response");
+        let _ = events.send(ConnectionEvent::UnexpectedIdleResponse(frame));
+    }
+}
+
 /// Time to wait for another command to send before starting the idle loop.
 const NEXT_COMMAND_IDLE_TIMEOUT: Duration = Duration::from_millis(100);
 
+/// Interval at which a warning is emitted while waiting for the response to a command.
+const PENDING_RESPONSE_WARNING: Duration = Duration::from_secs(30);
+
 async fn run_loop_iteration<C>(mut state: State<C>) -> Result<State<C>, ()>
 where
     C: AsyncRead + AsyncWrite + Unpin,
@@ -101,22 +188,42 @@ where
                 }
             }
         }
-        LoopState::WaitingForCommandReply(responder) => {
+        LoopState::WaitingForCommandReply(responder, partition, sent_at) => {
             // We're waiting for the response to the command associated with `responder`.
 
-            let response = state.connection.receive().await.transpose().ok_or(())?;
-            trace!("response to command received");
+            // If the connection was closed, dropping the responder notifies the client
+            let response = receive_command_response(&mut state.connection)
+                .await
+                .transpose()
+                .ok_or(())?;
+            let round_trip = sent_at.elapsed();
+            trace!(?round_trip, "response to command received");
+
+            if let (Some(partition), Ok(response)) = (partition, &response) {
+                if response.is_success() {
+                    debug!(?partition, "switched partition");
+                    state.partition = Some(partition);
+                }
+            }
 
-            let _ = responder.send(response.map_err(Into::into));
+            let _ = responder.send(
+                response
+                    .map(|response| (response, round_trip))
+                    .map_err(Into::into),
+            );
 
             let next_command = timeout(NEXT_COMMAND_IDLE_TIMEOUT, state.commands.recv());
 
             // See if we can immediately send the next command
             match next_command.await {
-                Ok(Some((command, responder))) => {
+                Ok(Some((command, responder, partition))) => {
                     trace!(?command, "next command immediately available");
+                    let sent_at = Instant::now();
                     match state.connection.send_list(command).await {
-                        Ok(_) => state.loop_state = LoopState::WaitingForCommandReply(responder),
+                        Ok(_) => {
+                            state.loop_state =
+                                LoopState::WaitingForCommandReply(responder, partition, sent_at);
+                        }
                         Err(e) => {
                             error!(error = ?e, "failed to send command");
                             let _ = responder.send(Err(e.into()));
@@ -145,14 +252,34 @@ where
     Ok(state)
 }
 
-async fn handle_command<C>(
-    state: &mut State<C>,
-    command: Option<(RawCommandList, CommandResponder)>,
-) -> Result<(), ()>
+/// Receive the response to a command, periodically emitting a warning while it is pending.
+async fn receive_command_response<C>(
+    connection: &mut AsyncConnection<C>,
+) -> Result<Option<Response>, MpdProtocolError>
 where
     C: AsyncRead + AsyncWrite + Unpin,
 {
-    let (command, responder) = command.ok_or(())?;
+    let started = Instant::now();
+    let receive = connection.receive();
+    tokio::pin!(receive);
+
+    // Don't cancel the receive operation while waiting, since that would lose any partial
+    // response received so far
+    loop {
+        tokio::select! {
+            response = &mut receive => break response,
+            _ = sleep(PENDING_RESPONSE_WARNING) => {
+                warn!(pending = ?started.elapsed(), "response to command is still pending");
+            }
+        }
+    }
+}
+
+async fn handle_command<C>(state: &mut State<C>, command: Option<CommandRequest>) -> Result<(), ()>
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    let (command, responder, partition) = command.ok_or(())?;
     trace!(?command, "command received");
 
     // Cancel currently ongoing idle
@@ -167,12 +294,7 @@ where
         Ok(None) => return Err(()),
         Ok(Some(res)) => match res.into_single_frame() {
             Ok(f) => {
-                if let Some(subsystem) = Subsystem::from_frame(f) {
-                    debug!(?subsystem, "state change");
-                    let _ = state
-                        .events
-                        .send(ConnectionEvent::SubsystemChange(subsystem));
-                }
+                emit_idle_events(&state.events, f, state.partition.as_ref());
             }
             Err(e) => {
                 error!(
@@ -195,8 +317,11 @@ where
 
     // Actually send the command. This sets the state for the next loop
     // iteration.
+    let sent_at = Instant::now();
     match state.connection.send_list(command).await {
-        Ok(_) => state.loop_state = LoopState::WaitingForCommandReply(responder),
+        Ok(_) => {
+            state.loop_state = LoopState::WaitingForCommandReply(responder, partition, sent_at)
+        }
         Err(e) => {
             error!(error = ?e, "failed to send command");
             let _ = responder.send(Err(e.into()));
@@ -221,12 +346,7 @@ where
         Ok(Some(res)) => {
             match res.into_single_frame() {
                 Ok(f) => {
-                    if let Some(subsystem) = Subsystem::from_frame(f) {
-                        debug!(?subsystem, "state change");
-                        let _ = state
-                            .events
-                            .send(ConnectionEvent::SubsystemChange(subsystem));
-                    }
+                    emit_idle_events(&state.events, f, state.partition.as_ref());
                 }
                 Err(e) => {
                     error!(code = e.code, message = e.message, "idle returned an error");
@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+
+use crate::{
+    client::{Client, CommandError, ConnectionEvent, ConnectionEvents, Subsystem},
+    commands::{ReadChannelMessages, SubscribeToChannel, UnsubscribeFromChannel},
+};
+
+/// Stream of messages received on [client-to-client channels][channels].
+///
+/// Whenever the server signals a change in the [`Message`][Subsystem::Message] subsystem, the
+/// pending messages are read automatically and yielded as `(channel, message)` pairs.
+///
+/// Since this consumes the [`ConnectionEvents`] of the connection, other events are discarded
+/// while waiting for messages. Use [`ChannelMessages::into_inner`] to get them back.
+///
+/// [channels]: https://www.musicpd.org/doc/html/protocol.html#client-to-client
+#[derive(Debug)]
+pub struct ChannelMessages {
+    client: Client,
+    events: ConnectionEvents,
+    pending: VecDeque<(String, String)>,
+}
+
+impl ChannelMessages {
+    /// Create a message stream from the components of a connection.
+    pub fn new(client: Client, events: ConnectionEvents) -> ChannelMessages {
+        ChannelMessages {
+            client,
+            events,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Subscribe to the given channel.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    pub async fn subscribe(&self, channel: &str) -> Result<(), CommandError> {
+        self.client.command(SubscribeToChannel(channel)).await
+    }
+
+    /// Unsubscribe from the given channel.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    pub async fn unsubscribe(&self, channel: &str) -> Result<(), CommandError> {
+        self.client.command(UnsubscribeFromChannel(channel)).await
+    }
+
+    /// Wait for the next message, as a `(channel, message)` pair.
+    ///
+    /// If this returns `None`, the connection was closed.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if reading the pending messages fails, in the same conditions as
+    /// [`Client::command`].
+    pub async fn next(&mut self) -> Option<Result<(String, String), CommandError>> {
+        loop {
+            if let Some(message) = self.pending.pop_front() {
+                return Some(Ok(message));
+            }
+
+            match self.events.next().await? {
+                ConnectionEvent::SubsystemChange(Subsystem::Message, _) => {
+                    match self.client.command(ReadChannelMessages).await {
+                        Ok(messages) => self.pending.extend(messages),
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                ConnectionEvent::ConnectionClosed(_) => return None,
+                _ => continue,
+            }
+        }
+    }
+
+    /// Get back the connection components.
+    ///
+    /// Messages that were read but not yet yielded are discarded.
+    pub fn into_inner(self) -> (Client, ConnectionEvents) {
+        (self.client, self.events)
+    }
+}
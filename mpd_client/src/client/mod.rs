@@ -1,12 +1,22 @@
 //! The client implementation.
 
 mod connection;
+mod messages;
+mod output_profile;
+mod queue_version;
+#[cfg(feature = "recording")]
+mod recording;
 
 use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    convert::Infallible,
     fmt,
     hash::{Hash, Hasher},
     io,
+    path::PathBuf,
+    str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 
 use bytes::BytesMut;
@@ -22,14 +32,28 @@ use tokio::{
         oneshot,
     },
 };
-use tracing::{debug, error, span, trace, warn, Instrument, Level};
-
+use tracing::{debug, error, span, trace, warn, Instrument, Level, Span};
+
+#[cfg(feature = "recording")]
+pub use self::recording::{Recorder, Replay};
+pub use self::{
+    messages::ChannelMessages,
+    output_profile::OutputProfile,
+    queue_version::{QueueSync, QueueVersionTracker},
+};
 use crate::{
-    commands::{self as cmds, Command, CommandList},
-    responses::TypedResponseError,
+    commands::{self as cmds, Command, CommandList, PartialCommandList},
+    filter::Filter,
+    responses::{Album, AlbumArt, Artist, Count, List, SongInQueue, TypedResponseError, UpdateJob},
+    tag::Tag,
 };
 
-type CommandResponder = oneshot::Sender<Result<RawResponse, CommandError>>;
+/// Sender for the response to a command, along with the round-trip time measured by the
+/// connection task.
+type CommandResponder = oneshot::Sender<Result<(RawResponse, Duration), CommandError>>;
+
+/// A command list to send, along with the name of the partition it switches to (if any).
+type CommandRequest = (RawCommandList, CommandResponder, Option<Arc<str>>);
 
 /// Components of a connection.
 ///
@@ -102,9 +126,17 @@ pub type Connection = (Client, ConnectionEvents);
 ///
 /// Cloning the `Client` is cheap and reuses the same connection it was initially given. Dropping
 /// the last clone of a particular `Client` will close the connection automatically.
+///
+/// # Timing
+///
+/// All timers used by the connection (such as the delay before idling again after a command) use
+/// the Tokio clock, so tests can run against a mock connection with [paused time] and will not
+/// wait in real time.
+///
+/// [paused time]: https://docs.rs/tokio/latest/tokio/time/fn.pause.html
 #[derive(Clone)]
 pub struct Client {
-    commands_sender: UnboundedSender<(RawCommandList, CommandResponder)>,
+    commands_sender: UnboundedSender<CommandRequest>,
     protocol_version: Arc<str>,
 }
 
@@ -177,6 +209,43 @@ impl Client {
         do_connect(connection, password).await
     }
 
+    /// Perform the initial handshake on the given connection (receiving the server greeting and
+    /// sending the password, if given), without starting a client.
+    ///
+    /// This is an escape hatch for driving the protocol directly, e.g. for proxies or custom
+    /// event loops. The returned connection can later be turned into a client using
+    /// [`Client::from_connection`].
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if the handshake fails, or if the password is incorrect.
+    pub async fn handshake<C>(
+        connection: C,
+        password: Option<&str>,
+    ) -> Result<AsyncConnection<C>, ConnectWithPasswordError>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        let span = span!(Level::DEBUG, "handshake");
+        handshake(connection, password, &span).await
+    }
+
+    /// Start a client on a connection which has already completed the initial handshake, such as
+    /// one returned by [`Client::handshake`] or [`AsyncConnection::connect`].
+    ///
+    /// The connection must not be in the middle of a command or an `idle`.
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    pub fn from_connection<C>(connection: AsyncConnection<C>) -> Connection
+    where
+        C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let span = span!(Level::DEBUG, "client connection");
+        spawn_run_loop(connection, &span)
+    }
+
     /// Send a [command].
     ///
     /// This will automatically parse the response to a proper type.
@@ -197,6 +266,27 @@ impl Client {
         Ok(response)
     }
 
+    /// Send a [command], and return the raw response frame alongside the typed response.
+    ///
+    /// This allows reading fields the typed response doesn't cover (yet) without sending the
+    /// command a second time using [`Client::raw_command`]. The returned frame contains all
+    /// fields of the response, including those that were converted.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    ///
+    /// [command]: super::commands
+    pub async fn command_with_frame<C>(&self, cmd: C) -> Result<(C::Response, Frame), CommandError>
+    where
+        C: Command,
+    {
+        let command = cmd.command();
+        let frame = self.raw_command(command).await?;
+        let response = cmd.response(frame.clone())?;
+        Ok((response, frame))
+    }
+
     /// Send the given command list, and return the (typed) responses.
     ///
     /// # Errors
@@ -215,6 +305,49 @@ impl Client {
         list.responses(frames).map_err(Into::into)
     }
 
+    /// Send the given command list, and return the (typed) responses.
+    ///
+    /// Unlike [`Client::command_list`], if one of the commands returns an error, the responses to
+    /// the commands that succeeded before it are converted and returned in the error.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`]. See
+    /// [`PartialCommandListError`] for the contained responses.
+    pub async fn command_list_partial<L>(
+        &self,
+        list: L,
+    ) -> Result<L::Response, PartialCommandListError<L::PartialResponse>>
+    where
+        L: PartialCommandList,
+    {
+        let frames = match list.command_list() {
+            Some(cmds) => self.raw_command_list(cmds).await,
+            None => Ok(Vec::new()),
+        };
+
+        match frames {
+            Ok(frames) => list.responses(frames).map_err(|e| PartialCommandListError {
+                responses: None,
+                error: e.into(),
+            }),
+            Err(CommandError::ErrorResponse {
+                error,
+                succesful_frames,
+            }) => Err(PartialCommandListError {
+                responses: list.partial_responses(succesful_frames.clone()).ok(),
+                error: CommandError::ErrorResponse {
+                    error,
+                    succesful_frames,
+                },
+            }),
+            Err(error) => Err(PartialCommandListError {
+                responses: None,
+                error,
+            }),
+        }
+    }
+
     /// Send the given command, and return the response to it.
     ///
     /// # Errors
@@ -249,21 +382,7 @@ impl Client {
         debug!(?commands, "sending command");
 
         let res = self.do_send(commands).await?;
-        let mut frames = Vec::with_capacity(res.successful_frames());
-
-        for frame in res {
-            match frame {
-                Ok(f) => frames.push(f),
-                Err(error) => {
-                    return Err(CommandError::ErrorResponse {
-                        error,
-                        succesful_frames: frames,
-                    });
-                }
-            }
-        }
-
-        Ok(frames)
+        response_frames(res)
     }
 
     /// Load album art for the given URI.
@@ -295,20 +414,11 @@ impl Client {
     ) -> Result<Option<(BytesMut, Option<String>)>, CommandError> {
         debug!("loading album art");
 
-        let mut out = BytesMut::new();
-        let mut expected_size = 0;
-        let mut embedded = false;
-        let mut mime = None;
-
         // Try loadding embedded album art first
         match self.command(cmds::AlbumArtEmbedded::new(uri)).await {
             Ok(Some(resp)) => {
-                out = resp.data;
-                expected_size = resp.size;
-                out.reserve(expected_size);
-                embedded = true;
-                mime = resp.mime;
-                debug!(length = resp.size, ?mime, "found embedded album art");
+                debug!(length = resp.size, mime = ?resp.mime, "found embedded album art");
+                return self.load_remaining_art(uri, true, resp).await;
             }
             Ok(None) => {
                 debug!("readpicture command gave no result, falling back");
@@ -321,17 +431,54 @@ impl Client {
             },
         }
 
-        if !embedded {
-            if let Some(resp) = self.command(cmds::AlbumArt::new(uri)).await? {
-                out = resp.data;
-                expected_size = resp.size;
-                out.reserve(expected_size);
-                debug!(length = expected_size, "found separate file album art");
-            } else {
-                debug!("no embedded or separate album art found");
-                return Ok(None);
-            }
+        if let Some(resp) = self.command(cmds::AlbumArt::new(uri)).await? {
+            debug!(length = resp.size, "found separate file album art");
+            self.load_remaining_art(uri, false, resp).await
+        } else {
+            debug!("no embedded or separate album art found");
+            Ok(None)
+        }
+    }
+
+    /// Load the picture embedded in the file at the given URI.
+    ///
+    /// Unlike [`Client::album_art`], this only uses the [`readpicture`][cmds::AlbumArtEmbedded]
+    /// command and never falls back to a separate file. The same note about the binary size
+    /// limit applies.
+    ///
+    /// # Return value
+    ///
+    /// Same as [`Client::album_art`].
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`]. In particular, servers
+    /// older than protocol version 0.22 will return an error response since they do not support
+    /// the command.
+    #[tracing::instrument(skip(self))]
+    pub async fn embedded_art(
+        &self,
+        uri: &str,
+    ) -> Result<Option<(BytesMut, Option<String>)>, CommandError> {
+        debug!("loading embedded art");
+
+        match self.command(cmds::AlbumArtEmbedded::new(uri)).await? {
+            Some(resp) => self.load_remaining_art(uri, true, resp).await,
+            None => Ok(None),
         }
+    }
+
+    /// Load the remaining chunks of a picture, given the response to the first command.
+    async fn load_remaining_art(
+        &self,
+        uri: &str,
+        embedded: bool,
+        first: AlbumArt,
+    ) -> Result<Option<(BytesMut, Option<String>)>, CommandError> {
+        let expected_size = first.size;
+        let mime = first.mime;
+        let mut out = first.data;
+        out.reserve(expected_size.saturating_sub(out.len()));
 
         while out.len() < expected_size {
             let resp = if embedded {
@@ -356,112 +503,646 @@ impl Client {
         Ok(Some((out, mime)))
     }
 
-    /// Get the protocol version the underlying connection is using.
-    pub fn protocol_version(&self) -> &str {
-        self.protocol_version.as_ref()
+    /// Add a local file that is not part of the database to the queue, returning its ID.
+    ///
+    /// The path is converted using [`Add::local_file_uri`][cmds::Add::local_file_uri], with its
+    /// file system checks running on a blocking thread.
+    ///
+    /// # Errors
+    ///
+    /// MPD only permits adding local files for clients connected via a local socket. If the server
+    /// rejects the command because the client is connected via TCP, this returns
+    /// [`AddLocalFileError::NotLocal`].
+    pub async fn add_local_file(
+        &self,
+        path: impl Into<PathBuf>,
+    ) -> Result<cmds::SongId, AddLocalFileError> {
+        let path = path.into();
+        let uri = tokio::task::spawn_blocking(move || cmds::Add::local_file_uri(&path))
+            .await
+            .unwrap_or_else(|e| Err(io::Error::other(e)))
+            .map_err(AddLocalFileError::InvalidPath)?;
+
+        match self.command(cmds::Add::uri(&uri)).await {
+            Ok(id) => Ok(id),
+            // ACK_ERROR_PERMISSION
+            Err(CommandError::ErrorResponse { error, .. }) if error.code == 4 => {
+                debug!(message = error.message, "adding local file not permitted");
+                Err(AddLocalFileError::NotLocal)
+            }
+            Err(e) => Err(AddLocalFileError::Command(e)),
+        }
     }
 
-    /// Returns `true` if the connection to the server has been closed (by the server or due to an
-    /// error).
-    pub fn is_connection_closed(&self) -> bool {
-        self.commands_sender.is_closed()
+    /// List the albums in the database, optionally only those containing songs matching the
+    /// given filter.
+    ///
+    /// Albums are distinguished by their name, album artist and MusicBrainz release ID, so
+    /// different albums with the same name are returned separately.
+    ///
+    /// This sends a command list consisting of a [`count`][cmds::CountGrouped] grouped by album
+    /// and a [`list`][cmds::List] of the album artists, MusicBrainz IDs and sort names. If
+    /// multiple albums share a name, a second command list with a [`count`][cmds::Count] for
+    /// each of them is sent.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`].
+    pub async fn albums(&self, filter: Option<Filter>) -> Result<Vec<Album>, CommandError> {
+        let (counts, details) = self
+            .library_entries(
+                Tag::Album,
+                [Tag::AlbumArtist, Tag::MusicBrainzReleaseId, Tag::AlbumSort],
+                filter.clone(),
+            )
+            .await?;
+        let releases = Album::releases(details.grouped_values());
+
+        let commands: Vec<_> = Album::ambiguous_releases(&releases)
+            .map(|release| {
+                // An empty value matches songs without the tag
+                let mut release_filter = Filter::tag(Tag::Album, release.name)
+                    .and(Filter::tag(Tag::AlbumArtist, release.artist))
+                    .and(Filter::tag(
+                        Tag::MusicBrainzReleaseId,
+                        release.musicbrainz_id,
+                    ));
+
+                if let Some(filter) = &filter {
+                    release_filter = release_filter.and(filter.clone());
+                }
+
+                cmds::Count::new(release_filter)
+            })
+            .collect();
+
+        let separate_counts = if commands.is_empty() {
+            Vec::new()
+        } else {
+            self.command_list(commands).await?
+        };
+
+        Ok(Album::from_responses(counts, &releases, separate_counts))
     }
 
-    async fn do_send(&self, commands: RawCommandList) -> Result<RawResponse, CommandError> {
-        let (tx, rx) = oneshot::channel();
+    /// List the artists in the database, optionally only those of songs matching the given
+    /// filter.
+    ///
+    /// This works like [`Client::albums`], using the `Artist`, `ArtistSort` and
+    /// `MUSICBRAINZ_ARTISTID` tags.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`].
+    pub async fn artists(&self, filter: Option<Filter>) -> Result<Vec<Artist>, CommandError> {
+        let (counts, details) = self
+            .library_entries(
+                Tag::Artist,
+                [Tag::ArtistSort, Tag::MusicBrainzArtistId],
+                filter,
+            )
+            .await?;
+        Ok(Artist::from_responses(counts, details.grouped_values()))
+    }
 
-        self.commands_sender
-            .send((commands, tx))
-            .map_err(|_| CommandError::ConnectionClosed)?;
+    async fn library_entries<const N: usize>(
+        &self,
+        tag: Tag,
+        details: [Tag; N],
+        filter: Option<Filter>,
+    ) -> Result<(Vec<(String, Count)>, List<N>), CommandError> {
+        let mut count = cmds::CountGrouped::new(tag.clone());
+        let mut list = cmds::List::new(tag).group_by(details);
+
+        if let Some(filter) = filter {
+            count = count.filter(filter.clone());
+            list = list.filter(filter);
+        }
 
-        rx.await.map_err(|_| CommandError::ConnectionClosed)?
+        self.command_list((count, list)).await
     }
-}
 
-impl fmt::Debug for Client {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Client")
-            .field("protocol_version", &self.protocol_version)
-            .finish_non_exhaustive()
+    /// Get the value of the sticker with the given name for each of the given songs.
+    ///
+    /// This sends a single command list of [`sticker list`][cmds::StickerList] commands, so
+    /// songs without the sticker result in `None` instead of an error. To get multiple stickers
+    /// of a single song, use [`StickerList`][cmds::StickerList] directly.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`], e.g. if one of
+    /// the songs does not exist.
+    pub async fn sticker_for_songs(
+        &self,
+        uris: &[&str],
+        name: &str,
+    ) -> Result<Vec<Option<String>>, CommandError> {
+        let commands = uris.iter().map(|uri| cmds::StickerList::new(uri)).collect();
+        let lists: Vec<_> = self.command_list::<Vec<_>>(commands).await?;
+
+        Ok(lists
+            .into_iter()
+            .map(|mut list| list.value.remove(name))
+            .collect())
     }
-}
 
-/// Perform the initial handshake to the server.
-async fn do_connect<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
-    io: IO,
-    password: Option<&str>,
-) -> Result<Connection, ConnectWithPasswordError> {
-    let span = span!(Level::DEBUG, "client connection");
+    /// Set multiple stickers on a song, using a single command list.
+    ///
+    /// Setting a sticker has no response, so there are no per-item results: either all stickers
+    /// are set, or an error is returned.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`]. If setting one of
+    /// the stickers fails, the remaining ones are not set. The number of stickers that were set
+    /// is the number of successful frames in the [error][CommandError::ErrorResponse].
+    pub async fn set_stickers(
+        &self,
+        uri: &str,
+        stickers: &[(&str, &str)],
+    ) -> Result<(), CommandError> {
+        let commands: Vec<_> = stickers
+            .iter()
+            .map(|(name, value)| cmds::StickerSet::new(uri, name, value))
+            .collect();
+
+        self.command_list(commands).await?;
+        Ok(())
+    }
 
-    let (state_changes_sender, state_changes) = unbounded_channel();
-    let (commands_sender, commands_receiver) = unbounded_channel();
+    /// Set the sticker with the given name to the same value on multiple songs, using a single
+    /// command list.
+    ///
+    /// Like [`Client::set_stickers`], this has no per-item results.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::set_stickers`].
+    pub async fn set_sticker_for_songs(
+        &self,
+        uris: &[&str],
+        name: &str,
+        value: &str,
+    ) -> Result<(), CommandError> {
+        let commands: Vec<_> = uris
+            .iter()
+            .map(|uri| cmds::StickerSet::new(uri, name, value))
+            .collect();
+
+        self.command_list(commands).await?;
+        Ok(())
+    }
 
-    let mut connection = match AsyncConnection::connect(io).instrument(span.clone()).await {
-        Ok(c) => c,
-        Err(e) => {
-            error!(error = ?e, "failed to perform initial handshake");
-            return Err(e.into());
-        }
-    };
+    /// Delete multiple stickers from a song.
+    ///
+    /// Returns whether each of the stickers existed (and was deleted). This sends a
+    /// [`sticker list`][cmds::StickerList] to determine the existing stickers, and a single
+    /// command list deleting them, since deleting a nonexistent sticker is an error.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`].
+    pub async fn delete_stickers(
+        &self,
+        uri: &str,
+        names: &[&str],
+    ) -> Result<Vec<bool>, CommandError> {
+        let existing = self.command(cmds::StickerList::new(uri)).await?.value;
+        let deleted: Vec<bool> = names.iter().map(|n| existing.contains_key(*n)).collect();
+
+        let commands: Vec<_> = names
+            .iter()
+            .zip(&deleted)
+            .filter(|(_, exists)| **exists)
+            .map(|(name, _)| cmds::StickerDelete::new(uri, name))
+            .collect();
+
+        self.command_list(commands).await?;
+        Ok(deleted)
+    }
 
-    let protocol_version = Arc::from(connection.protocol_version());
+    /// Delete the sticker with the given name from multiple songs.
+    ///
+    /// Returns whether the sticker existed (and was deleted) on each of the songs. This sends two
+    /// command lists, see [`Client::delete_stickers`].
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`].
+    pub async fn delete_sticker_for_songs(
+        &self,
+        uris: &[&str],
+        name: &str,
+    ) -> Result<Vec<bool>, CommandError> {
+        let deleted: Vec<bool> = self
+            .sticker_for_songs(uris, name)
+            .await?
+            .iter()
+            .map(Option::is_some)
+            .collect();
+
+        let commands: Vec<_> = uris
+            .iter()
+            .zip(&deleted)
+            .filter(|(_, exists)| **exists)
+            .map(|(uri, _)| cmds::StickerDelete::new(uri, name))
+            .collect();
+
+        self.command_list(commands).await?;
+        Ok(deleted)
+    }
 
-    if let Some(password) = password {
-        trace!(parent: &span, "sending password");
+    /// Send a [`ping`][cmds::Ping] to the server, and return the round-trip time.
+    ///
+    /// The time is measured by the connection task, from sending the command until its response
+    /// is received, so it does not include the time spent passing the command to and from the
+    /// connection task or cancelling an ongoing `idle`.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    pub async fn ping(&self) -> Result<Duration, CommandError> {
+        let command = RawCommandList::new(cmds::Ping.command());
+        let (response, round_trip) = self.do_send_timed(command, None).await?;
+        response_frames(response)?;
 
-        if let Err(e) = connection
-            .send(RawCommand::new("password").argument(password.to_owned()))
-            .instrument(span.clone())
-            .await
-        {
-            error!(parent: &span, error = ?e, "failed to send password");
-            return Err(e.into());
-        }
+        Ok(round_trip)
+    }
 
-        match connection.receive().instrument(span.clone()).await {
-            Err(e) => {
-                error!(parent: &span, error = ?e, "failed to receive reply to password");
-                return Err(e.into());
-            }
-            Ok(None) => {
-                error!(
-                    parent: &span,
-                    "unexpected end of stream after sending password"
-                );
-                return Err(MpdProtocolError::Io(io::Error::new(
-                    io::ErrorKind::UnexpectedEof,
-                    "connection closed while waiting for reply to password",
-                ))
-                .into());
-            }
-            Ok(Some(response)) if response.is_error() => {
-                error!(parent: &span, "incorrect password");
-                return Err(ConnectWithPasswordError::IncorrectPassword);
-            }
-            Ok(Some(_)) => {
-                trace!(parent: &span, "password accepted");
+    /// Get the songs in the queue, using the [`playlistinfo`][cmds::Queue] command.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    pub async fn queue(&self) -> Result<Vec<SongInQueue>, CommandError> {
+        self.command(cmds::Queue).await
+    }
+
+    /// Get the current song, using the [`currentsong`][cmds::CurrentSong] command.
+    ///
+    /// Returns `None` if there is no current song.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    pub async fn current_song(&self) -> Result<Option<SongInQueue>, CommandError> {
+        self.command(cmds::CurrentSong).await
+    }
+
+    /// Remove duplicate songs from the queue.
+    ///
+    /// Songs are considered duplicates if they have the same URI. Of every set of duplicates, only
+    /// the first occurrence in the queue is kept, unless one of them is the current song, in which
+    /// case that one is kept instead. The duplicates are removed by ID using a single command
+    /// list, so positions shifting during the removal do not matter.
+    ///
+    /// Returns the number of removed songs.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`].
+    #[tracing::instrument(skip(self))]
+    pub async fn dedupe_queue(&self) -> Result<usize, CommandError> {
+        let (queue, status) = self.command_list((cmds::Queue, cmds::Status)).await?;
+        let current = status.current_song.map(|(_, id)| id);
+
+        let mut kept = HashMap::with_capacity(queue.len());
+        let mut duplicates = Vec::new();
+
+        for song in &queue {
+            match kept.entry(song.song.url.as_str()) {
+                Entry::Vacant(e) => {
+                    e.insert(song.id);
+                }
+                Entry::Occupied(mut e) if Some(song.id) == current => {
+                    duplicates.push(cmds::Delete::id(e.insert(song.id)));
+                }
+                Entry::Occupied(_) => duplicates.push(cmds::Delete::id(song.id)),
             }
         }
+
+        let removed = duplicates.len();
+        debug!(removed, "removing duplicate songs");
+
+        self.command_list(duplicates).await?;
+
+        Ok(removed)
     }
 
-    tokio::spawn(
-        connection::run_loop(connection, commands_receiver, state_changes_sender)
-            .instrument(span!(parent: &span, Level::TRACE, "run loop")),
-    );
+    /// Run several database updates, and wait for them to finish.
+    ///
+    /// The given [`Update`][cmds::Update] or [`Rescan`][cmds::Rescan] commands are sent as a
+    /// single command list. Afterwards, this waits for database update events on the given
+    /// `events` and calls `progress` with the index of the command and its job whenever one of
+    /// the jobs has finished. Returns the jobs once all of them are finished.
+    ///
+    /// To wait for a single job, use [`UpdateJob::finished`]. Like it, this handles the job IDs
+    /// wrapping around.
+    ///
+    /// Events received on `events` while waiting are not lost, they are returned by
+    /// [`ConnectionEvents::next`] afterwards.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`], and if the
+    /// connection is closed before all jobs are finished.
+    #[tracing::instrument(skip_all)]
+    pub async fn update_many<C, F>(
+        &self,
+        commands: Vec<C>,
+        events: &mut ConnectionEvents,
+        mut progress: F,
+    ) -> Result<Vec<UpdateJob>, CommandError>
+    where
+        C: Command<Response = UpdateJob>,
+        F: FnMut(usize, UpdateJob),
+    {
+        let jobs = self.command_list(commands).await?;
+        let mut pending: Vec<(usize, UpdateJob)> = jobs.iter().copied().enumerate().collect();
 
-    let state_changes = ConnectionEvents(state_changes);
-    let client = Client {
-        commands_sender,
-        protocol_version,
-    };
+        debug!(?jobs, "started database updates");
 
-    Ok((client, state_changes))
-}
+        loop {
+            let running = self.command(cmds::Status).await?.update_job;
 
-/// Errors which can occur when issuing a command.
-#[derive(Debug)]
-pub enum CommandError {
-    /// The connection to MPD was closed cleanly
+            pending.retain(|&(index, job)| {
+                if job.is_finished(running) {
+                    trace!(job = job.id(), "database update finished");
+                    progress(index, job);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            if pending.is_empty() {
+                break;
+            }
+
+            events.next_update_event().await?;
+        }
+
+        Ok(jobs)
+    }
+
+    /// Get the protocol version the underlying connection is using.
+    pub fn protocol_version(&self) -> &str {
+        self.protocol_version.as_ref()
+    }
+
+    /// Switch the connection to the given [partition].
+    ///
+    /// Once the switch succeeds, the following [`SubsystemChange`] events are tagged with the new
+    /// partition. Switching partitions using [`Client::raw_command`] is not tracked.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::raw_command`], e.g. if the
+    /// partition does not exist.
+    ///
+    /// [partition]: https://mpd.readthedocs.io/en/latest/protocol.html#partition-commands
+    /// [`SubsystemChange`]: ConnectionEvent::SubsystemChange
+    pub async fn switch_partition(&self, name: &str) -> Result<(), CommandError> {
+        let command = RawCommand::new("partition").argument(name);
+
+        self.do_send_switching_partition(RawCommandList::new(command), Some(Arc::from(name)))
+            .await?
+            .into_single_frame()
+            .map_err(|error| CommandError::ErrorResponse {
+                error,
+                succesful_frames: Vec::new(),
+            })?;
+
+        Ok(())
+    }
+
+    /// Apply the given output profile.
+    ///
+    /// This first [switches][Client::switch_partition] to the partition of the profile (if any)
+    /// and gets the list of [outputs][cmds::Outputs] in it, and then sends a single command list
+    /// which enables the outputs in the profile, disables all other outputs, and sets the replay
+    /// gain mode (if any). If any of the commands fail, the remaining ones are not executed.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command_list`]. If switching the
+    /// partition fails, no outputs are changed.
+    #[tracing::instrument(skip_all, fields(profile = profile.name()))]
+    pub async fn apply_output_profile(&self, profile: &OutputProfile) -> Result<(), CommandError> {
+        if let Some(partition) = &profile.partition {
+            self.switch_partition(partition).await?;
+        }
+
+        let outputs = self.command(cmds::Outputs).await?;
+
+        let mut commands = Vec::with_capacity(outputs.len() + 1);
+
+        for output in outputs {
+            if profile.enables(output.id) {
+                commands.push(cmds::EnableOutput(output.id).command());
+            } else {
+                commands.push(cmds::DisableOutput(output.id).command());
+            }
+        }
+
+        if let Some(mode) = profile.replay_gain_mode {
+            commands.push(cmds::SetReplayGainMode(mode).command());
+        }
+
+        let mut commands = commands.into_iter();
+        let Some(first) = commands.next() else {
+            return Ok(());
+        };
+
+        let mut list = RawCommandList::new(first);
+        for command in commands {
+            list.add(command);
+        }
+
+        debug!(?list, "applying output profile");
+
+        let response = self.do_send(list).await?;
+        response_frames(response)?;
+
+        Ok(())
+    }
+
+    /// Returns `true` if the connection to the server has been closed (by the server or due to an
+    /// error).
+    pub fn is_connection_closed(&self) -> bool {
+        self.commands_sender.is_closed()
+    }
+
+    async fn do_send(&self, commands: RawCommandList) -> Result<RawResponse, CommandError> {
+        self.do_send_switching_partition(commands, None).await
+    }
+
+    async fn do_send_switching_partition(
+        &self,
+        commands: RawCommandList,
+        partition: Option<Arc<str>>,
+    ) -> Result<RawResponse, CommandError> {
+        let (response, _) = self.do_send_timed(commands, partition).await?;
+        Ok(response)
+    }
+
+    /// Send the commands, returning the response along with the round-trip time measured by the
+    /// connection task.
+    async fn do_send_timed(
+        &self,
+        commands: RawCommandList,
+        partition: Option<Arc<str>>,
+    ) -> Result<(RawResponse, Duration), CommandError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.commands_sender
+            .send((commands, tx, partition))
+            .map_err(|_| CommandError::ConnectionClosed)?;
+
+        rx.await.map_err(|_| CommandError::ConnectionClosed)?
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("protocol_version", &self.protocol_version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Collect the frames of a response, or return an error containing the successful frames if the
+/// response contains an error.
+fn response_frames(response: RawResponse) -> Result<Vec<Frame>, CommandError> {
+    let mut frames = Vec::with_capacity(response.successful_frames());
+
+    for frame in response {
+        match frame {
+            Ok(f) => frames.push(f),
+            Err(error) => {
+                return Err(CommandError::ErrorResponse {
+                    error,
+                    succesful_frames: frames,
+                });
+            }
+        }
+    }
+
+    Ok(frames)
+}
+
+/// Perform the initial handshake to the server.
+async fn do_connect<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
+    io: IO,
+    password: Option<&str>,
+) -> Result<Connection, ConnectWithPasswordError> {
+    let span = span!(Level::DEBUG, "client connection");
+    let connection = handshake(io, password, &span).await?;
+    Ok(spawn_run_loop(connection, &span))
+}
+
+/// Spawn the run loop on a connection which has completed the handshake.
+fn spawn_run_loop<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
+    connection: AsyncConnection<IO>,
+    span: &Span,
+) -> Connection {
+    let (state_changes_sender, state_changes) = unbounded_channel();
+    let (commands_sender, commands_receiver) = unbounded_channel();
+
+    let protocol_version = Arc::from(connection.protocol_version());
+
+    tokio::spawn(
+        connection::run_loop(connection, commands_receiver, state_changes_sender)
+            .instrument(span!(parent: span, Level::TRACE, "run loop")),
+    );
+
+    let state_changes = ConnectionEvents::new(state_changes);
+    let client = Client {
+        commands_sender,
+        protocol_version,
+    };
+
+    (client, state_changes)
+}
+
+/// Receive the server greeting and send the password, if given.
+async fn handshake<IO: AsyncRead + AsyncWrite + Unpin>(
+    io: IO,
+    password: Option<&str>,
+    span: &Span,
+) -> Result<AsyncConnection<IO>, ConnectWithPasswordError> {
+    let mut connection = match AsyncConnection::connect(io).instrument(span.clone()).await {
+        Ok(c) => c,
+        Err(e) => {
+            error!(error = ?e, "failed to perform initial handshake");
+            return Err(e.into());
+        }
+    };
+
+    if let Some(password) = password {
+        trace!(parent: span, "sending password");
+
+        if let Err(e) = connection
+            .send(cmds::Password(password).command())
+            .instrument(span.clone())
+            .await
+        {
+            error!(parent: span, error = ?e, "failed to send password");
+            return Err(e.into());
+        }
+
+        match connection.receive().instrument(span.clone()).await {
+            Err(e) => {
+                error!(parent: span, error = ?e, "failed to receive reply to password");
+                return Err(e.into());
+            }
+            Ok(None) => {
+                error!(
+                    parent: span,
+                    "unexpected end of stream after sending password"
+                );
+                return Err(MpdProtocolError::Io(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed while waiting for reply to password",
+                ))
+                .into());
+            }
+            Ok(Some(response)) if response.is_error() => {
+                error!(parent: span, "incorrect password");
+                return Err(ConnectWithPasswordError::IncorrectPassword);
+            }
+            Ok(Some(_)) => {
+                trace!(parent: span, "password accepted");
+            }
+        }
+    }
+
+    Ok(connection)
+}
+
+/// Perform the initial handshake and start an idle-only loop.
+async fn do_listen<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
+    io: IO,
+    password: Option<&str>,
+) -> Result<ConnectionEvents, ConnectWithPasswordError> {
+    let span = span!(Level::DEBUG, "listener connection");
+
+    let (state_changes_sender, state_changes) = unbounded_channel();
+
+    let connection = handshake(io, password, &span).await?;
+
+    tokio::spawn(
+        connection::run_idle_loop(connection, state_changes_sender)
+            .instrument(span!(parent: &span, Level::TRACE, "idle loop")),
+    );
+
+    Ok(ConnectionEvents::new(state_changes))
+}
+
+/// Errors which can occur when issuing a command.
+#[derive(Debug)]
+pub enum CommandError {
+    /// The connection to MPD was closed cleanly
     ConnectionClosed,
     /// An underlying protocol error occurred, including IO errors
     Protocol(MpdProtocolError),
@@ -504,6 +1185,14 @@ impl fmt::Display for CommandError {
     }
 }
 
+impl CommandError {
+    /// Returns `true` if this is an error response indicating that a password was not accepted.
+    pub fn is_incorrect_password(&self) -> bool {
+        // ACK_ERROR_PASSWORD
+        matches!(self, CommandError::ErrorResponse { error, .. } if error.code == 3)
+    }
+}
+
 impl std::error::Error for CommandError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -528,6 +1217,30 @@ impl From<TypedResponseError> for CommandError {
     }
 }
 
+/// Error returned by [`Client::command_list_partial`].
+#[derive(Debug)]
+pub struct PartialCommandListError<P> {
+    /// The typed responses to the commands that succeeded before the error.
+    ///
+    /// This is only present if the error is a [`CommandError::ErrorResponse`] and the successful
+    /// frames could be converted.
+    pub responses: Option<P>,
+    /// The underlying error.
+    pub error: CommandError,
+}
+
+impl<P> fmt::Display for PartialCommandListError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<P: fmt::Debug> std::error::Error for PartialCommandListError<P> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
 /// Error returned when [connecting with a password][Client::connect_with_password] fails.
 #[derive(Debug)]
 pub enum ConnectWithPasswordError {
@@ -562,30 +1275,170 @@ impl From<MpdProtocolError> for ConnectWithPasswordError {
     }
 }
 
+/// Error returned by [`Client::add_local_file`].
+#[derive(Debug)]
+pub enum AddLocalFileError {
+    /// The path does not refer to a readable file, or is not absolute.
+    InvalidPath(io::Error),
+    /// The server does not permit adding local files, because the client is not connected via a
+    /// local socket.
+    NotLocal,
+    /// An unrelated error occurred while sending the command.
+    Command(CommandError),
+}
+
+impl fmt::Display for AddLocalFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddLocalFileError::InvalidPath(_) => write!(f, "invalid local file path"),
+            AddLocalFileError::NotLocal => {
+                write!(f, "local files can only be added via a local socket")
+            }
+            AddLocalFileError::Command(_) => write!(f, "failed to add local file"),
+        }
+    }
+}
+
+impl std::error::Error for AddLocalFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AddLocalFileError::InvalidPath(e) => Some(e),
+            AddLocalFileError::Command(e) => Some(e),
+            AddLocalFileError::NotLocal => None,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<CommandError> for AddLocalFileError {
+    fn from(e: CommandError) -> Self {
+        AddLocalFileError::Command(e)
+    }
+}
+
 /// Receiver for [connection events][ConnectionEvent].
 ///
 /// This includes notifications about state changes as well as the connection being closed,
 /// possibly due to an error. If you don't care about these, you can just drop this receiver.
 #[derive(Debug)]
-pub struct ConnectionEvents(pub(crate) UnboundedReceiver<ConnectionEvent>);
+pub struct ConnectionEvents {
+    events: UnboundedReceiver<ConnectionEvent>,
+    /// Events received while waiting for a specific event, which are returned by `next` first.
+    buffered: VecDeque<ConnectionEvent>,
+}
+
+impl ConnectionEvents {
+    fn new(events: UnboundedReceiver<ConnectionEvent>) -> ConnectionEvents {
+        ConnectionEvents {
+            events,
+            buffered: VecDeque::new(),
+        }
+    }
+}
 
 impl ConnectionEvents {
+    /// Listen for events on the MPD server using the given connection, without a [`Client`].
+    ///
+    /// The connection is used exclusively for receiving state change notifications, so this is
+    /// suitable for monitoring programs that never send commands. The connection is closed when
+    /// the returned value is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if sending the initial commands over the given transport fails.
+    pub async fn listen<C>(connection: C) -> Result<ConnectionEvents, MpdProtocolError>
+    where
+        C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        do_listen(connection, None).await.map_err(|e| match e {
+            ConnectWithPasswordError::ProtocolError(e) => e,
+            ConnectWithPasswordError::IncorrectPassword => unreachable!(),
+        })
+    }
+
+    /// Listen for events on the password-protected MPD server using the given connection and
+    /// password, without a [`Client`].
+    ///
+    /// See [`ConnectionEvents::listen`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if sending the initial commands over the given transport fails,
+    /// or if the password is incorrect.
+    pub async fn listen_with_password<C>(
+        connection: C,
+        password: &str,
+    ) -> Result<ConnectionEvents, ConnectWithPasswordError>
+    where
+        C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        do_listen(connection, Some(password)).await
+    }
+
     /// Wait for the next connection event.
     ///
     /// If this returns `None`, the connection was closed cleanly.
     pub async fn next(&mut self) -> Option<ConnectionEvent> {
-        self.0.recv().await
+        match self.buffered.pop_front() {
+            Some(event) => Some(event),
+            None => self.events.recv().await,
+        }
     }
-}
 
-/// Events that occur during connection life cycle.
-#[derive(Debug)]
-pub enum ConnectionEvent {
-    /// A change event in one of the subsystems of the server occurred.
-    SubsystemChange(Subsystem),
-    /// The connection was closed because of an error.
-    ConnectionClosed(ConnectionError),
-}
+    /// Wait for the next `database` or `update` event.
+    ///
+    /// All events received while waiting, including the one this waited for, are kept and
+    /// returned by later calls to [`ConnectionEvents::next`].
+    pub(crate) async fn next_update_event(&mut self) -> Result<(), CommandError> {
+        loop {
+            let Some(event) = self.events.recv().await else {
+                return Err(CommandError::ConnectionClosed);
+            };
+
+            let result = match &event {
+                ConnectionEvent::SubsystemChange(Subsystem::Update | Subsystem::Database, _) => {
+                    Some(Ok(()))
+                }
+                ConnectionEvent::ConnectionClosed(_) => Some(Err(CommandError::ConnectionClosed)),
+                _ => None,
+            };
+
+            self.buffered.push_back(event);
+
+            if let Some(result) = result {
+                return result;
+            }
+        }
+    }
+}
+
+/// Events that occur during connection life cycle.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConnectionEvent {
+    /// A change event in one of the subsystems of the server occurred.
+    ///
+    /// The second field is the partition the event belongs to, if the connection was switched to
+    /// it using [`Client::switch_partition`]. Otherwise, the event belongs to the partition the
+    /// connection started in (usually `default`).
+    SubsystemChange(Subsystem, Option<Arc<str>>),
+    /// The connection was closed because of an error.
+    ConnectionClosed(ConnectionError),
+    /// A response to an `idle` command contained fields other than state change notifications.
+    ///
+    /// This usually indicates a misbehaving server or proxy. The frame is the entire response,
+    /// including any state change fields, which are also emitted as separate
+    /// [`SubsystemChange`][ConnectionEvent::SubsystemChange] events.
+    UnexpectedIdleResponse(Frame),
+}
 
 /// Subsystems of MPD which can receive state change notifications.
 ///
@@ -617,8 +1470,8 @@ pub enum Subsystem {
 }
 
 impl Subsystem {
-    fn from_frame(mut r: Frame) -> Option<Subsystem> {
-        r.get("changed").map(|raw| match &*raw {
+    fn from_raw(raw: &str) -> Subsystem {
+        match raw {
             "database" => Subsystem::Database,
             "message" => Subsystem::Message,
             "mixer" => Subsystem::Mixer,
@@ -634,7 +1487,7 @@ impl Subsystem {
             "neighbor" => Subsystem::Neighbor,
             "mount" => Subsystem::Mount,
             _ => Subsystem::Other(raw.into()),
-        })
+        }
     }
 
     /// Returns the raw protocol name used for this subsystem.
@@ -659,94 +1512,696 @@ impl Subsystem {
     }
 }
 
+/// Parses a protocol subsystem name. Unknown names result in the `Other` variant.
+impl FromStr for Subsystem {
+    type Err = Infallible;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Ok(Subsystem::from_raw(raw))
+    }
+}
+
+/// Renders the protocol name of the subsystem, see [`Subsystem::as_str`].
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl PartialEq for Subsystem {
     fn eq(&self, other: &Self) -> bool {
         self.as_str() == other.as_str()
     }
 }
 
-impl Eq for Subsystem {}
+impl Eq for Subsystem {}
+
+impl Hash for Subsystem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+/// Errors which result in the connection being closed.
+#[derive(Debug)]
+pub enum ConnectionError {
+    /// An underlying protocol error occurred, including IO errors.
+    Protocol(MpdProtocolError),
+    /// An invalid response was received (such as in response to the `idle` commands).
+    InvalidResponse,
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionError::Protocol(_) => write!(f, "protocol error"),
+            ConnectionError::InvalidResponse => write!(f, "invalid response"),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConnectionError::Protocol(e) => Some(e),
+            ConnectionError::InvalidResponse => None,
+        }
+    }
+}
+
+impl From<MpdProtocolError> for ConnectionError {
+    fn from(e: MpdProtocolError) -> Self {
+        ConnectionError::Protocol(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use assert_matches::assert_matches;
+    use mpd_protocol::command::escape_argument;
+    use tokio_test::io::Builder as MockBuilder;
+
+    use super::*;
+    use crate::responses::{FromFrames, ReplayGainStatus};
+
+    static GREETING: &[u8] = b"OK MPD 0.21.11\n";
+
+    #[tokio::test(start_paused = true)]
+    async fn single_state_change() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .read(b"changed: player\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (_client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Player, None))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn switch_partition() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"changed: player\nOK\n")
+            .write(b"partition bar\n")
+            .read(b"ACK [50@0] {partition} partition does not exist\n")
+            .write(b"partition foo\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .read(b"changed: mixer\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let err = client.switch_partition("bar").await.unwrap_err();
+        assert_matches!(err, CommandError::ErrorResponse { error, .. } if error.code == 50);
+        client
+            .switch_partition("foo")
+            .await
+            .expect("command failed");
+
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Player, None))
+        );
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Mixer, Some(p))) if &*p == "foo"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unexpected_idle_response() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .read(b"changed: player\nchanged: mixer\nfoo: bar\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (_client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Player, None))
+        );
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Mixer, None))
+        );
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::UnexpectedIdleResponse(frame)) if frame.find("foo") == Some("bar")
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn listen() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .read(b"changed: player\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: mixer\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let mut events = ConnectionEvents::listen(io).await.expect("connect failed");
+
+        assert_matches!(
+            events.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Player, None))
+        );
+        assert_matches!(
+            events.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Mixer, None))
+        );
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn command_with_frame() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"getvol\n")
+            .read(b"volume: 42\nfoo: bar\nOK\n")
+            .build();
+
+        let (client, _) = Client::connect(io).await.expect("connect failed");
+
+        let (volume, frame) = client
+            .command_with_frame(cmds::GetVolume)
+            .await
+            .expect("command failed");
+
+        assert_eq!(volume, Some(42));
+        assert_eq!(frame.find("volume"), Some("42"));
+        assert_eq!(frame.find("foo"), Some("bar"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn command_list_partial() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\ngetvol\nplay\ngetvol\ncommand_list_end\n")
+            .read(b"volume: 42\nlist_OK\nACK [50@1] {play} No such song\n")
+            .write(b"command_list_ok_begin\ngetvol\ngetvol\ncommand_list_end\n")
+            .read(b"volume: 42\nlist_OK\nACK [5@1] {} unknown command \"getvol\"\n")
+            .build();
+
+        let (client, _) = Client::connect(io).await.expect("connect failed");
+
+        let err = client
+            .command_list_partial((cmds::GetVolume, cmds::Play::current(), cmds::GetVolume))
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.responses, Some((Some(Some(42)), None, None)));
+        assert_matches!(
+            err.error,
+            CommandError::ErrorResponse { error, succesful_frames } if error.code == 50 && succesful_frames.len() == 1
+        );
+
+        let err = client
+            .command_list_partial(vec![cmds::GetVolume, cmds::GetVolume])
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.responses, Some(vec![Some(42)]));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn batch_stickers() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nsticker list song a.mp3\nsticker list song b.mp3\nsticker list song c.mp3\ncommand_list_end\n")
+            .read(b"sticker: rating=4\nlist_OK\nlist_OK\nsticker: rating=3\nlist_OK\nOK\n")
+            .write(b"command_list_ok_begin\nsticker set song a.mp3 rating 5\nsticker set song a.mp3 played 1\ncommand_list_end\n")
+            .read(b"list_OK\nlist_OK\nOK\n")
+            .write(b"sticker list song a.mp3\n")
+            .read(b"sticker: rating=5\nsticker: played=1\nOK\n")
+            .write(b"sticker delete song a.mp3 rating\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nsticker list song a.mp3\nsticker list song b.mp3\nsticker list song c.mp3\ncommand_list_end\n")
+            .read(b"sticker: played=1\nlist_OK\nlist_OK\nsticker: played=1\nlist_OK\nOK\n")
+            .write(b"command_list_ok_begin\nsticker delete song a.mp3 played\nsticker delete song c.mp3 played\ncommand_list_end\n")
+            .read(b"list_OK\nlist_OK\nOK\n")
+            .build();
+
+        let (client, _) = Client::connect(io).await.expect("connect failed");
+        let songs = ["a.mp3", "b.mp3", "c.mp3"];
+
+        let ratings = client
+            .sticker_for_songs(&songs, "rating")
+            .await
+            .expect("command failed");
+        assert_eq!(
+            ratings,
+            [Some(String::from("4")), None, Some(String::from("3"))]
+        );
+
+        client
+            .set_stickers("a.mp3", &[("rating", "5"), ("played", "1")])
+            .await
+            .expect("command failed");
+
+        let deleted = client
+            .delete_stickers("a.mp3", &["rating", "skipped"])
+            .await
+            .expect("command failed");
+        assert_eq!(deleted, [true, false]);
+
+        let deleted = client
+            .delete_sticker_for_songs(&songs, "played")
+            .await
+            .expect("command failed");
+        assert_eq!(deleted, [true, false, true]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn incorrect_password() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"password foo\n")
+            .read(b"ACK [3@0] {password} incorrect password\n")
+            .build();
+
+        let (client, _) = Client::connect(io).await.expect("connect failed");
+
+        let error = client
+            .command(cmds::Password("foo"))
+            .await
+            .expect_err("command succeeded");
+        assert!(error.is_incorrect_password());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn handshake_then_client() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"password foo\n")
+            .read(b"OK\n")
+            .write(b"hello\n")
+            .read(b"foo: bar\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: player\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let mut connection = Client::handshake(io, Some("foo"))
+            .await
+            .expect("handshake failed");
+        assert_eq!(connection.protocol_version(), "0.21.11");
+
+        let response = connection
+            .command(RawCommand::new("hello"))
+            .await
+            .expect("command failed");
+        assert_eq!(
+            response.into_single_frame().unwrap().find("foo"),
+            Some("bar")
+        );
+
+        let (_client, mut state_changes) = Client::from_connection(connection);
+
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Player, None))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn command() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"changed: playlist\nOK\n")
+            .write(b"hello\n")
+            .read(b"foo: bar\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let response = client
+            .raw_command(RawCommand::new("hello"))
+            .await
+            .expect("command failed");
+
+        assert_eq!(response.find("foo"), Some("bar"));
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Queue, None))
+        );
+        assert!(state_changes.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn prepared_command() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"replay_gain_status\n")
+            .read(b"replay_gain_mode: off\nOK\n")
+            .write(b"replay_gain_status\n")
+            .read(b"replay_gain_mode: track\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let prepared = cmds::PreparedCommand::new(cmds::ReplayGainStatus);
+
+        let first = client.command(&prepared).await.expect("command failed");
+        let second = client.command(&prepared).await.expect("command failed");
+
+        assert_eq!(first.mode, cmds::ReplayGainMode::Off);
+        assert_eq!(second.mode, cmds::ReplayGainMode::Track);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn queue() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"playlistinfo\n")
+            .read(b"file: a.mp3\nPos: 0\nId: 4\nfile: b.mp3\nPos: 1\nId: 7\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _) = Client::connect(io).await.expect("connect failed");
+
+        let queue = client.queue().await.expect("command failed");
+        let ids: Vec<_> = queue.iter().map(|song| song.id).collect();
+        assert_eq!(ids, [cmds::SongId(4), cmds::SongId(7)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn current_song() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"currentsong\n")
+            .read(b"file: a.mp3\nTitle: Foo\nPos: 2\nId: 9\nOK\n")
+            .write(b"currentsong\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _) = Client::connect(io).await.expect("connect failed");
+
+        let song = client
+            .current_song()
+            .await
+            .expect("command failed")
+            .unwrap();
+        assert_eq!(song.position, cmds::SongPosition(2));
+        assert_eq!(song.song.title(), Some("Foo"));
+
+        let song = client.current_song().await.expect("command failed");
+        assert_eq!(song, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dedupe_queue() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nplaylistinfo\nstatus\ncommand_list_end\n")
+            .read(b"file: a.mp3\nPos: 0\nId: 1\n")
+            .read(b"file: b.mp3\nPos: 1\nId: 2\n")
+            .read(b"file: a.mp3\nPos: 2\nId: 3\n")
+            .read(b"file: b.mp3\nPos: 3\nId: 4\n")
+            .read(b"file: a.mp3\nPos: 4\nId: 5\nlist_OK\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: play\nsong: 3\nsongid: 4\n")
+            .read(b"list_OK\nOK\n")
+            .write(b"command_list_ok_begin\ndeleteid 3\ndeleteid 2\ndeleteid 5\ncommand_list_end\n")
+            .read(b"list_OK\nlist_OK\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
 
-impl Hash for Subsystem {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.as_str().hash(state);
+        let removed = client.dedupe_queue().await.expect("command failed");
+        assert_eq!(removed, 3);
     }
-}
 
-/// Errors which result in the connection being closed.
-#[derive(Debug)]
-pub enum ConnectionError {
-    /// An underlying protocol error occurred, including IO errors.
-    Protocol(MpdProtocolError),
-    /// An invalid response was received (such as in response to the `idle` commands).
-    InvalidResponse,
-}
+    #[tokio::test(start_paused = true)]
+    async fn update_many() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nupdate foo\nupdate bar\ncommand_list_end\n")
+            .read(b"updating_db: 4\nlist_OK\nupdating_db: 5\nlist_OK\nOK\n")
+            .write(b"status\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nupdating_db: 4\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: update\nOK\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"status\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nupdating_db: 5\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: database\nchanged: update\nOK\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"status\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nOK\n")
+            .write(b"idle\n")
+            .build();
 
-impl fmt::Display for ConnectionError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ConnectionError::Protocol(_) => write!(f, "protocol error"),
-            ConnectionError::InvalidResponse => write!(f, "invalid response"),
-        }
-    }
-}
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
 
-impl std::error::Error for ConnectionError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            ConnectionError::Protocol(e) => Some(e),
-            ConnectionError::InvalidResponse => None,
+        let mut finished = Vec::new();
+        let jobs = client
+            .update_many(
+                vec![
+                    cmds::Update::new().uri("foo"),
+                    cmds::Update::new().uri("bar"),
+                ],
+                &mut state_changes,
+                |index, job| finished.push((index, job)),
+            )
+            .await
+            .expect("command failed");
+
+        assert_eq!(jobs, [UpdateJob::new(4), UpdateJob::new(5)]);
+        assert_eq!(finished, [(0, jobs[0]), (1, jobs[1])]);
+
+        for subsystem in [Subsystem::Update, Subsystem::Database, Subsystem::Update] {
+            assert_matches!(
+                state_changes.next().await,
+                Some(ConnectionEvent::SubsystemChange(s, None)) if s == subsystem
+            );
         }
     }
-}
 
-impl From<MpdProtocolError> for ConnectionError {
-    fn from(e: MpdProtocolError) -> Self {
-        ConnectionError::Protocol(e)
+    #[tokio::test(start_paused = true)]
+    async fn update_job_finished() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"update\n")
+            .read(b"updating_db: 7\nOK\n")
+            .write(b"status\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nupdating_db: 7\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: player\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: update\nOK\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"status\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nupdating_db: 8\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let job = client
+            .command(cmds::Update::new())
+            .await
+            .expect("command failed");
+        assert_eq!(job.id(), 7);
+
+        job.finished(&client, &mut state_changes)
+            .await
+            .expect("waiting for job failed");
+
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Player, None))
+        );
+        assert_matches!(
+            state_changes.next().await,
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Update, None))
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::collections::hash_map::DefaultHasher;
+    #[tokio::test(start_paused = true)]
+    async fn apply_output_profile() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"partition desk\n")
+            .read(b"OK\n")
+            .write(b"outputs\n")
+            .read(b"outputid: 0\noutputname: Speakers\noutputenabled: 1\noutputid: 1\noutputname: Headphones\noutputenabled: 0\nOK\n")
+            .write(b"command_list_ok_begin\ndisableoutput 0\nenableoutput 1\nreplay_gain_mode track\ncommand_list_end\n")
+            .read(b"list_OK\nlist_OK\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            .build();
 
-    use assert_matches::assert_matches;
-    use tokio_test::io::Builder as MockBuilder;
+        let (client, _) = Client::connect(io).await.expect("connect failed");
 
-    use super::*;
+        let profile = OutputProfile::new("headphones", [1])
+            .partition("desk")
+            .replay_gain_mode(cmds::ReplayGainMode::Track);
 
-    static GREETING: &[u8] = b"OK MPD 0.21.11\n";
+        client
+            .apply_output_profile(&profile)
+            .await
+            .expect("command failed");
+    }
 
-    #[tokio::test]
-    async fn single_state_change() {
+    #[tokio::test(start_paused = true)]
+    async fn apply_output_profile_error() {
         let io = MockBuilder::new()
             .read(GREETING)
             .write(b"idle\n")
-            .read(b"changed: player\nOK\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"partition desk\n")
+            .read(b"OK\n")
+            .write(b"outputs\n")
+            .read(b"outputid: 0\noutputname: Speakers\noutputenabled: 1\noutputid: 1\noutputname: Headphones\noutputenabled: 0\nOK\n")
+            .write(b"command_list_ok_begin\ndisableoutput 0\nenableoutput 1\nreplay_gain_mode track\ncommand_list_end\n")
+            .read(b"list_OK\nACK [50@1] {enableoutput} No such audio output\n")
+            .write(b"idle\n")
+            .read(b"changed: output\nOK\n")
             .write(b"idle\n")
             .build();
 
-        let (_client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let profile = OutputProfile::new("headphones", [1])
+            .partition("desk")
+            .replay_gain_mode(cmds::ReplayGainMode::Track);
+
+        let err = client.apply_output_profile(&profile).await.unwrap_err();
+        assert_matches!(
+            err,
+            CommandError::ErrorResponse { error, succesful_frames } if error.command_index == 1 && succesful_frames.len() == 1
+        );
 
         assert_matches!(
             state_changes.next().await,
-            Some(ConnectionEvent::SubsystemChange(Subsystem::Player))
+            Some(ConnectionEvent::SubsystemChange(Subsystem::Output, Some(p))) if &*p == "desk"
         );
     }
 
-    #[tokio::test]
-    async fn command() {
+    #[tokio::test(start_paused = true)]
+    async fn ping() {
         let io = MockBuilder::new()
             .read(GREETING)
             .write(b"idle\n")
             .write(b"noidle\n")
-            .read(b"changed: playlist\nOK\n")
+            .wait(Duration::from_millis(50))
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .wait(Duration::from_millis(20))
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        // The time spent cancelling the idle is not included
+        let rtt = client.ping().await.expect("command failed");
+        assert!(rtt >= Duration::from_millis(20));
+        assert!(rtt < Duration::from_millis(50));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn add_local_file_not_local() {
+        let path = std::env::current_exe().unwrap();
+        let uri = format!("file://{}", path.to_str().unwrap());
+        let command = format!("addid {}\n", escape_argument(&uri));
+
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(command.as_bytes())
+            .read(b"ACK [4@0] {addid} Permission denied\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let err = client.add_local_file(path).await.unwrap_err();
+        assert_matches!(err, AddLocalFileError::NotLocal);
+
+        let err = client.add_local_file("foo/bar.mp3").await.unwrap_err();
+        assert_matches!(err, AddLocalFileError::InvalidPath(_));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_response() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
             .write(b"hello\n")
-            .read(b"foo: bar\nOK\n")
+            .read(b"foo: bar\n")
+            .wait(Duration::from_secs(100))
+            .read(b"baz: qux\nOK\n")
             .write(b"idle\n")
             .build();
 
-        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
 
         let response = client
             .raw_command(RawCommand::new("hello"))
@@ -754,14 +2209,10 @@ mod tests {
             .expect("command failed");
 
         assert_eq!(response.find("foo"), Some("bar"));
-        assert_matches!(
-            state_changes.next().await,
-            Some(ConnectionEvent::SubsystemChange(Subsystem::Queue))
-        );
-        assert!(state_changes.next().await.is_none());
+        assert_eq!(response.find("baz"), Some("qux"));
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn incomplete_response() {
         let io = MockBuilder::new()
             .read(GREETING)
@@ -784,7 +2235,7 @@ mod tests {
         assert_eq!(response.find("foo"), Some("bar"));
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn command_list() {
         let io = MockBuilder::new()
             .read(GREETING)
@@ -811,7 +2262,61 @@ mod tests {
         assert_eq!(responses[0].find("foo"), Some("asdf"));
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
+    async fn command_list_combined() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_begin\nfoo\nbar\ncommand_list_end\n")
+            .read(b"foo: asdf\nbaz: qux\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let commands = RawCommandList::new(RawCommand::new("foo"))
+            .command(RawCommand::new("bar"))
+            .combined();
+
+        let responses = client
+            .raw_command_list(commands)
+            .await
+            .expect("command failed");
+
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].find("foo"), Some("asdf"));
+        assert_eq!(responses[0].find("baz"), Some("qux"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn command_list_from_frames() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nreplay_gain_status\nping\ncommand_list_end\n")
+            .read(b"replay_gain_mode: album\nlist_OK\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let commands = RawCommandList::new(RawCommand::new("replay_gain_status"))
+            .command(RawCommand::new("ping"));
+
+        let frames = client
+            .raw_command_list(commands)
+            .await
+            .expect("command failed");
+
+        let (status, ()) = <(ReplayGainStatus, ())>::from_frames(frames).expect("invalid response");
+        assert_eq!(status.mode, cmds::ReplayGainMode::Album);
+    }
+
+    #[tokio::test(start_paused = true)]
     async fn dropping_client() {
         let io = MockBuilder::new().read(GREETING).write(b"idle\n").build();
 
@@ -822,7 +2327,7 @@ mod tests {
         assert!(state_changes.next().await.is_none());
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn album_art() {
         let io = MockBuilder::new()
             .read(GREETING)
@@ -848,7 +2353,7 @@ mod tests {
         );
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn album_art_fallback() {
         let io = MockBuilder::new()
             .read(GREETING)
@@ -873,7 +2378,38 @@ mod tests {
         assert_eq!(x, Some((BytesMut::from("FOOBAR"), None)));
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
+    async fn album_art_detect_mime() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"readpicture foo/bar.mp3 0\n")
+            .read(b"OK\n")
+            .write(b"albumart foo/bar.mp3 0\n")
+            .read(b"size: 11\nbinary: 8\n\x89PNG\r\n\x1A\n\nOK\n")
+            .write(b"albumart foo/bar.mp3 8\n")
+            .read(b"size: 11\nbinary: 3\nEND\nOK\n")
+            .build();
+
+        let (client, _) = Client::connect(io).await.expect("connect failed");
+
+        let x = client
+            .album_art("foo/bar.mp3")
+            .await
+            .expect("command failed");
+
+        assert_eq!(
+            x,
+            Some((
+                BytesMut::from(&b"\x89PNG\r\n\x1A\nEND"[..]),
+                Some(String::from("image/png"))
+            ))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
     async fn album_art_fallback_error() {
         let io = MockBuilder::new()
             .read(GREETING)
@@ -898,7 +2434,43 @@ mod tests {
         assert_eq!(x, Some((BytesMut::from("FOOBAR"), None)));
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
+    async fn embedded_art() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"readpicture foo/bar.mp3 0\n")
+            .read(b"size: 6\ntype: image/png\nbinary: 3\nFOO\nOK\n")
+            .write(b"readpicture foo/bar.mp3 3\n")
+            .read(b"size: 6\ntype: image/png\nbinary: 3\nBAR\nOK\n")
+            .write(b"readpicture foo/baz.mp3 0\n")
+            .read(b"OK\n")
+            .build();
+
+        let (client, _) = Client::connect(io).await.expect("connect failed");
+
+        let x = client
+            .embedded_art("foo/bar.mp3")
+            .await
+            .expect("command failed");
+
+        assert_eq!(
+            x,
+            Some((BytesMut::from("FOOBAR"), Some(String::from("image/png"))))
+        );
+
+        // No fallback to `albumart`
+        let x = client
+            .embedded_art("foo/baz.mp3")
+            .await
+            .expect("command failed");
+
+        assert_eq!(x, None);
+    }
+
+    #[tokio::test(start_paused = true)]
     async fn album_art_none() {
         let io = MockBuilder::new()
             .read(GREETING)
@@ -921,7 +2493,7 @@ mod tests {
         assert_eq!(x, None);
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn protocol_version() {
         let io = MockBuilder::new().read(GREETING).write(b"idle\n").build();
 
@@ -930,6 +2502,33 @@ mod tests {
         assert_eq!(client.protocol_version(), "0.21.11");
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn channel_messages() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .read(b"changed: message\nOK\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"readmessages\n")
+            .read(b"channel: foo\nmessage: hello\nchannel: bar\nmessage: world\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, events) = Client::connect(io).await.expect("connect failed");
+        let mut messages = ChannelMessages::new(client, events);
+
+        assert_eq!(
+            messages.next().await.unwrap().unwrap(),
+            (String::from("foo"), String::from("hello"))
+        );
+        assert_eq!(
+            messages.next().await.unwrap().unwrap(),
+            (String::from("bar"), String::from("world"))
+        );
+    }
+
     #[test]
     fn subsystem_equality() {
         assert_eq!(Subsystem::Player, Subsystem::Other("player".into()));
@@ -942,4 +2541,13 @@ mod tests {
 
         assert_eq!(a.finish(), b.finish());
     }
+
+    #[test]
+    fn subsystem_round_trip() {
+        for subsystem in [Subsystem::Queue, Subsystem::Other("foo".into())] {
+            assert_eq!(subsystem.to_string().parse(), Ok(subsystem));
+        }
+
+        assert_eq!(Subsystem::Queue.to_string(), "playlist");
+    }
 }
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+
+use crate::commands::ReplayGainMode;
+
+/// A named set of enabled outputs, along with the partition and replay gain mode to use with
+/// them, e.g. for switching between speakers and headphones.
+///
+/// Apply a profile using [`Client::apply_output_profile`][crate::Client::apply_output_profile].
+///
+/// ```
+/// use mpd_client::{client::OutputProfile, commands::ReplayGainMode};
+///
+/// let headphones = OutputProfile::new("headphones", [1])
+///     .partition("default")
+///     .replay_gain_mode(ReplayGainMode::Track);
+///
+/// assert_eq!(headphones.name(), "headphones");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputProfile {
+    name: String,
+    pub(super) outputs: HashSet<u32>,
+    pub(super) partition: Option<String>,
+    pub(super) replay_gain_mode: Option<ReplayGainMode>,
+}
+
+impl OutputProfile {
+    /// Create a profile with the given name, which enables the outputs with the given IDs and
+    /// disables all others.
+    pub fn new<I>(name: impl Into<String>, outputs: I) -> OutputProfile
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        OutputProfile {
+            name: name.into(),
+            outputs: outputs.into_iter().collect(),
+            partition: None,
+            replay_gain_mode: None,
+        }
+    }
+
+    /// Switch to the given partition before changing the outputs.
+    pub fn partition(mut self, partition: impl Into<String>) -> OutputProfile {
+        self.partition = Some(partition.into());
+        self
+    }
+
+    /// Set the replay gain mode when applying the profile.
+    pub fn replay_gain_mode(mut self, mode: ReplayGainMode) -> OutputProfile {
+        self.replay_gain_mode = Some(mode);
+        self
+    }
+
+    /// Returns the name of the profile.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the profile enables the output with the given ID.
+    pub fn enables(&self, output: u32) -> bool {
+        self.outputs.contains(&output)
+    }
+}
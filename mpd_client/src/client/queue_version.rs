@@ -0,0 +1,77 @@
+/// Tracks the version of the queue across [`Status`] responses, for synchronizing a local copy of
+/// the queue incrementally.
+///
+/// MPD increments the queue version on every change, but the version is reset when it reaches
+/// 2<sup>31</sup> or when the server restarts. In both cases, the changes since the previous
+/// version can no longer be determined, and the entire queue needs to be reloaded.
+///
+/// ```
+/// use mpd_client::client::{QueueSync, QueueVersionTracker};
+///
+/// let mut tracker = QueueVersionTracker::new();
+///
+/// assert_eq!(tracker.update(5), QueueSync::Reload);
+/// assert_eq!(tracker.update(5), QueueSync::Unchanged);
+/// assert_eq!(tracker.update(7), QueueSync::Changes { since: 5 });
+/// assert_eq!(tracker.update(1), QueueSync::Reload);
+/// assert_eq!(tracker.changes(), 3);
+/// ```
+///
+/// [`Status`]: crate::responses::Status
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueueVersionTracker {
+    version: Option<u32>,
+    changes: u64,
+}
+
+/// How to synchronize the queue after a version update, as returned by
+/// [`QueueVersionTracker::update`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueueSync {
+    /// The queue did not change.
+    Unchanged,
+    /// The queue changed, and the changes can be loaded using
+    /// [`QueueChanges`][crate::commands::QueueChanges] with the given version.
+    Changes {
+        /// The previously seen version.
+        since: u32,
+    },
+    /// The version was reset (or is seen for the first time), so the entire queue needs to be
+    /// loaded.
+    Reload,
+}
+
+impl QueueVersionTracker {
+    /// Create a tracker which has not seen a version yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the tracker with the current queue version (as in
+    /// [`Status::playlist_version`][crate::responses::Status::playlist_version]).
+    pub fn update(&mut self, version: u32) -> QueueSync {
+        let sync = match self.version {
+            Some(previous) if previous == version => return QueueSync::Unchanged,
+            Some(previous) if previous < version => QueueSync::Changes { since: previous },
+            _ => QueueSync::Reload,
+        };
+
+        self.version = Some(version);
+        self.changes += 1;
+
+        sync
+    }
+
+    /// Returns the most recently seen queue version.
+    pub fn version(&self) -> Option<u32> {
+        self.version
+    }
+
+    /// Returns the number of times the queue changed (or was reset) since the tracker was
+    /// created.
+    ///
+    /// Unlike the version reported by the server, this only ever increases.
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+}
@@ -0,0 +1,361 @@
+//! Recording and replaying the traffic of connections.
+
+use std::{
+    io::{self, Write},
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Marker for data sent to the server.
+const SENT: u8 = b'>';
+/// Marker for data received from the server.
+const RECEIVED: u8 = b'<';
+
+/// Transport wrapper which records all traffic passing through it.
+///
+/// Every chunk of data that is sent or received is written to the sink, in a format that can be
+/// replayed using [`Replay`]. Each chunk consists of a header line containing the direction (`>`
+/// for sent, `<` for received) and the length of the data, followed by the raw data and a
+/// newline:
+///
+/// ```text
+/// < 14
+/// OK MPD 0.23.5
+///
+/// > 5
+/// idle
+///
+/// ```
+///
+/// Use this to wrap the connection passed to [`Client::connect`][crate::Client::connect].
+#[derive(Debug)]
+pub struct Recorder<T, W> {
+    inner: T,
+    sink: W,
+}
+
+impl<T, W> Recorder<T, W>
+where
+    W: Write,
+{
+    /// Wrap the given connection, writing the recorded traffic to `sink`.
+    pub fn new(inner: T, sink: W) -> Self {
+        Self { inner, sink }
+    }
+
+    /// Returns the wrapped connection and the sink.
+    pub fn into_inner(self) -> (T, W) {
+        (self.inner, self.sink)
+    }
+
+    fn record(&mut self, direction: u8, data: &[u8]) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(self.sink, "{} {}", direction as char, data.len())?;
+        self.sink.write_all(data)?;
+        self.sink.write_all(b"\n")?;
+        self.sink.flush()
+    }
+}
+
+impl<T, W> AsyncRead for Recorder<T, W>
+where
+    T: AsyncRead + Unpin,
+    W: Write + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+
+        match Pin::new(&mut this.inner).poll_read(cx, buf) {
+            Poll::Ready(Ok(())) => Poll::Ready(this.record(RECEIVED, &buf.filled()[before..])),
+            other => other,
+        }
+    }
+}
+
+impl<T, W> AsyncWrite for Recorder<T, W>
+where
+    T: AsyncWrite + Unpin,
+    W: Write + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        match Pin::new(&mut this.inner).poll_write(cx, buf) {
+            Poll::Ready(Ok(written)) => {
+                Poll::Ready(this.record(SENT, &buf[..written]).map(|()| written))
+            }
+            other => other,
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[derive(Debug)]
+struct Chunk {
+    sent: bool,
+    data: Vec<u8>,
+}
+
+/// Position in the list of chunks.
+#[derive(Debug, Default)]
+struct Cursor {
+    chunk: usize,
+    offset: usize,
+}
+
+/// Transport which replays traffic recorded using a [`Recorder`].
+///
+/// Reading yields the recorded received data, and writes are checked against the recorded sent
+/// data. Received data only becomes available once everything that was sent before it in the
+/// recording has been written. Writing unexpected data results in an error.
+///
+/// Use this in place of a real connection with [`Client::connect`][crate::Client::connect], e.g.
+/// for reproducing a parsing error with a recording of an affected server.
+#[derive(Debug)]
+pub struct Replay {
+    chunks: Vec<Chunk>,
+    read: Cursor,
+    write: Cursor,
+    read_waker: Option<Waker>,
+}
+
+impl Replay {
+    /// Create a replay of the given recording.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the recording is malformed.
+    pub fn new(recording: &[u8]) -> io::Result<Self> {
+        let mut chunks = Vec::new();
+        let mut rest = recording;
+
+        while !rest.is_empty() {
+            let (chunk, remaining) = parse_chunk(rest)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed recording"))?;
+
+            chunks.push(chunk);
+            rest = remaining;
+        }
+
+        Ok(Self {
+            chunks,
+            read: Cursor::default(),
+            write: Cursor::default(),
+            read_waker: None,
+        })
+    }
+
+    /// Advance the read or write cursor to the next chunk in that direction which has remaining
+    /// data, and return its index.
+    fn next_chunk(&mut self, sent: bool) -> usize {
+        let cursor = if sent {
+            &mut self.write
+        } else {
+            &mut self.read
+        };
+
+        while let Some(chunk) = self.chunks.get(cursor.chunk) {
+            if chunk.sent == sent && cursor.offset < chunk.data.len() {
+                break;
+            }
+
+            cursor.chunk += 1;
+            cursor.offset = 0;
+        }
+
+        cursor.chunk
+    }
+}
+
+fn parse_chunk(raw: &[u8]) -> Option<(Chunk, &[u8])> {
+    let header_end = raw.iter().position(|&b| b == b'\n')?;
+    let header = std::str::from_utf8(&raw[..header_end]).ok()?;
+
+    let sent = match *header.as_bytes().first()? {
+        SENT => true,
+        RECEIVED => false,
+        _ => return None,
+    };
+
+    let len: usize = header.get(2..)?.parse().ok()?;
+    let rest = &raw[header_end + 1..];
+
+    if rest.len() <= len || rest[len] != b'\n' {
+        return None;
+    }
+
+    let chunk = Chunk {
+        sent,
+        data: rest[..len].to_vec(),
+    };
+
+    Some((chunk, &rest[len + 1..]))
+}
+
+impl AsyncRead for Replay {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let read = this.next_chunk(false);
+        let write = this.next_chunk(true);
+
+        if read == this.chunks.len() && write == this.chunks.len() {
+            // Everything has been replayed, signal EOF
+            return Poll::Ready(Ok(()));
+        }
+
+        if read == this.chunks.len() || write < read {
+            // Waiting for the client to send the data preceding the next received chunk
+            this.read_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let data = &this.chunks[read].data[this.read.offset..];
+        let len = data.len().min(buf.remaining());
+        buf.put_slice(&data[..len]);
+        this.read.offset += len;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for Replay {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let write = this.next_chunk(true);
+
+        let Some(chunk) = this.chunks.get(write) else {
+            return Poll::Ready(Err(unexpected_write(buf, b"")));
+        };
+
+        let expected = &chunk.data[this.write.offset..];
+        let len = buf.len().min(expected.len());
+
+        if buf[..len] != expected[..len] {
+            return Poll::Ready(Err(unexpected_write(buf, expected)));
+        }
+
+        this.write.offset += len;
+
+        if let Some(waker) = this.read_waker.take() {
+            waker.wake();
+        }
+
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn unexpected_write(actual: &[u8], expected: &[u8]) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "unexpected write {:?}, expected {:?}",
+            String::from_utf8_lossy(actual),
+            String::from_utf8_lossy(expected),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_test::io::Builder as MockBuilder;
+
+    use super::*;
+    use crate::{
+        client::{Client, CommandError},
+        commands as cmds,
+    };
+
+    static RECORDING: &[u8] = b"< 14\nOK MPD 0.23.3\n\n\
+        > 5\nidle\n\n\
+        > 7\nnoidle\n\n\
+        < 3\nOK\n\n\
+        > 7\ngetvol\n\n\
+        < 14\nvolume: 42\nOK\n\n\
+        > 5\nidle\n\n";
+
+    #[tokio::test]
+    async fn record() {
+        let io = MockBuilder::new()
+            .read(b"OK MPD 0.23.3\n")
+            .write(b"ping\n")
+            .build();
+
+        let mut recorder = Recorder::new(io, Vec::new());
+
+        let mut greeting = [0; 14];
+        recorder.read_exact(&mut greeting).await.unwrap();
+        recorder.write_all(b"ping\n").await.unwrap();
+
+        let (_, recording) = recorder.into_inner();
+        assert_eq!(recording, b"< 14\nOK MPD 0.23.3\n\n> 5\nping\n\n");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay() {
+        let (client, _) = Client::connect(Replay::new(RECORDING).unwrap())
+            .await
+            .expect("connect failed");
+
+        let volume = client
+            .command(cmds::GetVolume)
+            .await
+            .expect("command failed");
+        assert_eq!(volume, Some(42));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_unexpected_write() {
+        let (client, _) = Client::connect(Replay::new(RECORDING).unwrap())
+            .await
+            .expect("connect failed");
+
+        let err = client.command(cmds::Status).await.unwrap_err();
+        assert!(matches!(err, CommandError::Protocol(_)));
+    }
+
+    #[test]
+    fn malformed_recording() {
+        assert!(Replay::new(b"").is_ok());
+        assert!(Replay::new(b"< 3\nOK\n").is_err());
+        assert!(Replay::new(b"< 4\nOK\n\n").is_err());
+        assert!(Replay::new(b"? 3\nOK\n\n").is_err());
+    }
+}
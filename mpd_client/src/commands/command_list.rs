@@ -23,6 +23,27 @@ pub trait CommandList {
     fn responses(self, frames: Vec<Frame>) -> Result<Self::Response, TypedResponseError>;
 }
 
+/// Command lists whose responses can be partially converted when one of the commands fails.
+///
+/// Used by [`Client::command_list_partial`][crate::Client::command_list_partial].
+pub trait PartialCommandList: CommandList {
+    /// The responses to the commands that succeeded before the failing one.
+    type PartialResponse;
+
+    /// Convert the raw response frames of the commands that succeeded into the proper response
+    /// type(s).
+    ///
+    /// There may be fewer frames than commands in the list, but more frames are an error.
+    ///
+    /// # Errors
+    ///
+    /// This should return an error if any of the responses were invalid.
+    fn partial_responses(
+        self,
+        frames: Vec<Frame>,
+    ) -> Result<Self::PartialResponse, TypedResponseError>;
+}
+
 /// Arbitrarily long sequence of the same command.
 impl<C> CommandList for Vec<C>
 where
@@ -50,6 +71,28 @@ where
     }
 }
 
+/// Returns the responses to the commands that succeeded, in order.
+impl<C> PartialCommandList for Vec<C>
+where
+    C: Command,
+{
+    type PartialResponse = Vec<C::Response>;
+
+    fn partial_responses(
+        self,
+        frames: Vec<Frame>,
+    ) -> Result<Self::PartialResponse, TypedResponseError> {
+        if frames.len() > self.len() {
+            return Err(TypedResponseError::other());
+        }
+
+        self.into_iter()
+            .zip(frames)
+            .map(|(command, frame)| command.response(frame))
+            .collect()
+    }
+}
+
 macro_rules! impl_command_list_tuple {
     ($first_type:ident, $($further_type:ident => $further_idx:tt),*) => {
         impl<$first_type, $($further_type),*> CommandList for ($first_type, $($further_type),*)
@@ -83,6 +126,40 @@ macro_rules! impl_command_list_tuple {
                 ))
             }
         }
+
+        /// Returns `None` for the commands that did not succeed.
+        impl<$first_type, $($further_type),*> PartialCommandList for ($first_type, $($further_type),*)
+        where
+            $first_type: Command,
+            $(
+                $further_type: Command
+            ),*
+        {
+            type PartialResponse = (
+                Option<$first_type::Response>,
+                $(Option<$further_type::Response>),*
+            );
+
+            fn partial_responses(
+                self,
+                frames: Vec<Frame>,
+            ) -> Result<Self::PartialResponse, TypedResponseError> {
+                let mut frames = frames.into_iter();
+
+                let responses = (
+                    frames.next().map(|f| self.0.response(f)).transpose()?,
+                    $(
+                        frames.next().map(|f| self.$further_idx.response(f)).transpose()?,
+                    )*
+                );
+
+                if frames.next().is_some() {
+                    return Err(TypedResponseError::other());
+                }
+
+                Ok(responses)
+            }
+        }
     };
 }
 
@@ -94,3 +171,31 @@ impl_command_list_tuple!(A, B => 1, C => 2, D => 3, E => 4);
 impl_command_list_tuple!(A, B => 1, C => 2, D => 3, E => 4, F => 5);
 impl_command_list_tuple!(A, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
 impl_command_list_tuple!(A, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{commands::GetVolume, responses::test_frames};
+
+    #[test]
+    fn partial_responses() {
+        let frames = test_frames(b"volume: 42\nlist_OK\nvolume: 43\nlist_OK\nOK\n");
+
+        assert_eq!(
+            vec![GetVolume, GetVolume, GetVolume]
+                .partial_responses(frames.clone())
+                .unwrap(),
+            [Some(42), Some(43)]
+        );
+        assert_eq!(
+            (GetVolume, GetVolume, GetVolume)
+                .partial_responses(frames.clone())
+                .unwrap(),
+            (Some(Some(42)), Some(Some(43)), None)
+        );
+
+        // More frames than commands
+        assert!(vec![GetVolume].partial_responses(frames.clone()).is_err());
+        assert!((GetVolume,).partial_responses(frames).is_err());
+    }
+}
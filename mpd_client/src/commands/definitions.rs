@@ -2,8 +2,12 @@
 
 use std::{
     cmp::min,
-    fmt::Write,
+    fmt::{self, Write},
+    fs, io,
+    marker::PhantomData,
     ops::{Bound, RangeBounds},
+    path::Path,
+    str::FromStr,
     time::Duration,
 };
 
@@ -14,9 +18,12 @@ use mpd_protocol::{
 };
 
 use crate::{
-    commands::{Command, ReplayGainMode, SeekMode, SingleMode, Song, SongId, SongPosition},
+    commands::{
+        Command, ConsumeMode, ReplayGainMode, SaveMode, SeekMode, SingleMode, Song, SongId,
+        SongPosition, Window,
+    },
     filter::Filter,
-    responses::{self as res, value, TypedResponseError},
+    responses::{self as res, optional_value, value, FromFrame, TypedResponseError},
     tag::Tag,
 };
 
@@ -78,22 +85,52 @@ macro_rules! single_arg_command {
     };
 }
 
+argless_command!(ClearError, "clearerror");
 argless_command!(ClearQueue, "clear");
 argless_command!(Next, "next");
 argless_command!(Ping, "ping");
 argless_command!(Previous, "previous");
 argless_command!(Stop, "stop");
+argless_command!(TogglePause, "pause");
 
 single_arg_command!(ClearPlaylist<'a>, &'a str, "playlistclear");
 single_arg_command!(DeletePlaylist<'a>, &'a str, "rm");
-single_arg_command!(SaveQueueAsPlaylist<'a>, &'a str, "save");
-single_arg_command!(SetConsume, bool, "consume");
 single_arg_command!(SetPause, bool, "pause");
 single_arg_command!(SetRandom, bool, "random");
 single_arg_command!(SetRepeat, bool, "repeat");
 single_arg_command!(SubscribeToChannel<'a>, &'a str, "subscribe");
 single_arg_command!(UnsubscribeFromChannel<'a>, &'a str, "unsubscribe");
 
+/// `password` command.
+///
+/// Prefer passing the password when connecting (e.g. using [`Client::connect_with_password`]),
+/// since the permissions of the connection are otherwise restricted until this command is sent.
+/// Use [`CommandError::is_incorrect_password`] to detect a rejected password.
+///
+/// [`Client::connect_with_password`]: crate::Client::connect_with_password
+/// [`CommandError::is_incorrect_password`]: crate::client::CommandError::is_incorrect_password
+#[derive(Clone, PartialEq, Eq)]
+pub struct Password<'a>(pub &'a str);
+
+impl<'a> fmt::Debug for Password<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Avoid leaking the password into logs
+        f.debug_tuple("Password").field(&"<redacted>").finish()
+    }
+}
+
+impl<'a> Command for Password<'a> {
+    type Response = ();
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("password").argument(self.0)
+    }
+
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
+    }
+}
+
 /// `replay_gain_status` command.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ReplayGainStatus;
@@ -158,6 +195,84 @@ impl Command for Queue {
     }
 }
 
+impl Queue {
+    /// Limit the result to the given window of queue positions.
+    pub fn window<R>(self, window: R) -> QueueWindow
+    where
+        R: RangeBounds<usize>,
+    {
+        QueueWindow(Window::from_range(window))
+    }
+
+    /// Only get the given song, by position (`playlistinfo POS`) or by ID (`playlistid ID`).
+    pub fn song<S>(self, song: S) -> QueueEntry
+    where
+        S: Into<Song>,
+    {
+        QueueEntry(song.into())
+    }
+}
+
+/// `playlistinfo` command limited to a window of the queue.
+///
+/// Returned by [`Queue::window`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueWindow(Window);
+
+impl Command for QueueWindow {
+    type Response = Vec<res::SongInQueue>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("playlistinfo").argument(self.0)
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::SongInQueue::from_frame_multi(frame)
+    }
+}
+
+/// `playlistinfo` or `playlistid` command for a single song in the queue.
+///
+/// Returned by [`Queue::song`]. The server responds with an error if the song does not exist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueEntry(Song);
+
+impl Command for QueueEntry {
+    type Response = Option<res::SongInQueue>;
+
+    fn command(&self) -> RawCommand {
+        match self.0 {
+            Song::Position(pos) => RawCommand::new("playlistinfo").argument(pos),
+            Song::Id(id) => RawCommand::new("playlistid").argument(id),
+        }
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::SongInQueue::from_frame_single(frame)
+    }
+}
+
+/// `plchanges` command.
+///
+/// Get the songs in the queue which changed since the given queue version (see
+/// [`Status::playlist_version`][res::Status::playlist_version]). Use a
+/// [`QueueVersionTracker`][crate::client::QueueVersionTracker] to determine whether the changes
+/// can be loaded incrementally.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueChanges(pub u32);
+
+impl Command for QueueChanges {
+    type Response = Vec<res::SongInQueue>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("plchanges").argument(self.0)
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::SongInQueue::from_frame_multi(frame)
+    }
+}
+
 /// `currentsong` command.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct CurrentSong;
@@ -237,6 +352,72 @@ impl<'a> Command for GetPlaylist<'a> {
     }
 }
 
+impl<'a> GetPlaylist<'a> {
+    /// Limit the result to the given window of playlist positions.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.24.
+    pub fn window<R>(self, window: R) -> GetPlaylistWindow<'a>
+    where
+        R: RangeBounds<usize>,
+    {
+        GetPlaylistWindow {
+            name: self.0,
+            window: Window::from_range(window),
+        }
+    }
+}
+
+/// `listplaylistinfo` command limited to a window of the playlist.
+///
+/// Returned by [`GetPlaylist::window`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GetPlaylistWindow<'a> {
+    name: &'a str,
+    window: Window,
+}
+
+impl<'a> Command for GetPlaylistWindow<'a> {
+    type Response = Vec<res::Song>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("listplaylistinfo")
+            .argument(self.name)
+            .argument(self.window)
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::Song::from_frame_multi(frame)
+    }
+}
+
+/// `listplaylist` command.
+///
+/// Like [`GetPlaylist`], but only returns the URIs of the songs in the playlist, which is faster
+/// for large playlists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GetPlaylistUris<'a>(pub &'a str);
+
+impl<'a> Command for GetPlaylistUris<'a> {
+    type Response = Vec<String>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("listplaylist").argument(self.0)
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        let mut out = Vec::with_capacity(frame.fields_len());
+        for (key, value) in frame {
+            if &*key != "file" {
+                return Err(TypedResponseError::unexpected_field("file", &*key));
+            }
+
+            out.push(value);
+        }
+
+        Ok(out)
+    }
+}
+
 /// `setvol` command.
 ///
 /// Set the volume. The value is truncated to fit in the range `0..=100`.
@@ -256,6 +437,40 @@ impl Command for SetVolume {
     }
 }
 
+/// `getvol` command.
+///
+/// Get the current volume, or `None` if there is no mixer. Requires protocol version 0.23.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GetVolume;
+
+impl Command for GetVolume {
+    type Response = Option<u8>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("getvol")
+    }
+
+    fn response(self, mut frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        optional_value(&mut frame, "volume")
+    }
+}
+
+/// `consume` command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetConsume(pub ConsumeMode);
+
+impl Command for SetConsume {
+    type Response = ();
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("consume").argument(self.0.as_str())
+    }
+
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
+    }
+}
+
 /// `single` command.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SetSingle(pub SingleMode);
@@ -264,13 +479,7 @@ impl Command for SetSingle {
     type Response = ();
 
     fn command(&self) -> RawCommand {
-        let single = match self.0 {
-            SingleMode::Disabled => "0",
-            SingleMode::Enabled => "1",
-            SingleMode::Oneshot => "oneshot",
-        };
-
-        RawCommand::new("single").argument(single)
+        RawCommand::new("single").argument(self.0.as_str())
     }
 
     fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
@@ -320,6 +529,48 @@ impl Command for Crossfade {
     }
 }
 
+/// `mixrampdb` command.
+///
+/// Sets the MixRamp threshold in decibels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MixRampDb(pub f32);
+
+impl Command for MixRampDb {
+    type Response = ();
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("mixrampdb").argument(self.0.to_string())
+    }
+
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
+    }
+}
+
+/// `mixrampdelay` command.
+///
+/// Sets the MixRamp delay. Passing `None` disables MixRamp overlapping and falls back to
+/// crossfading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MixRampDelay(pub Option<Duration>);
+
+impl Command for MixRampDelay {
+    type Response = ();
+
+    fn command(&self) -> RawCommand {
+        let command = RawCommand::new("mixrampdelay");
+
+        match self.0 {
+            Some(delay) => command.argument(delay),
+            None => command.argument("nan"),
+        }
+    }
+
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
+    }
+}
+
 /// `seek` and `seekid` commands.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SeekTo(pub Song, pub Duration);
@@ -438,6 +689,52 @@ impl<'a> Add<'a> {
         }
     }
 
+    /// Convert the given absolute path to a local file into the `file://` URI expected by MPD.
+    ///
+    /// Adding local files that are not part of the database is only permitted for clients
+    /// connected via a local socket. Over TCP, the server will reject the resulting command with a
+    /// permission error. [`Client::add_local_file`] reports this as a distinct error.
+    ///
+    /// **Note**: This uses blocking file system operations, so it should not be called directly
+    /// from async code. [`Client::add_local_file`] runs it on a blocking thread.
+    ///
+    /// # Errors
+    ///
+    /// This checks that the path refers to a readable file, and returns an error if it doesn't.
+    /// An error with kind [`InvalidInput`] is returned if the path is not absolute or not valid
+    /// UTF-8.
+    ///
+    /// [`Client::add_local_file`]: crate::Client::add_local_file
+    /// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
+    pub fn local_file_uri(path: &Path) -> io::Result<String> {
+        if !path.is_absolute() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "local file path must be absolute",
+            ));
+        }
+
+        let Some(path_str) = path.to_str() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "local file path must be valid UTF-8",
+            ));
+        };
+
+        if !fs::metadata(path)?.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "local file path does not refer to a file",
+            ));
+        }
+
+        // Make sure the file is readable by us, the server will most likely run with fewer
+        // permissions anyway
+        fs::File::open(path)?;
+
+        Ok(format!("file://{path_str}"))
+    }
+
     /// Add the URI at the given position in the queue.
     pub fn at<P: Into<SongPosition>>(mut self, position: P) -> Self {
         self.position = Some(PositionOrRelative::Absolute(position.into()));
@@ -490,13 +787,7 @@ pub struct Delete(Target);
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Target {
     Id(SongId),
-    Range(SongRange),
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct SongRange {
-    from: usize,
-    to: Option<usize>,
+    Range(Window),
 }
 
 impl Delete {
@@ -507,7 +798,7 @@ impl Delete {
 
     /// Remove the song at the given position from the queue.
     pub fn position(pos: SongPosition) -> Self {
-        let range = SongRange::new(pos..=pos);
+        let range = Window::from_positions(pos..=pos);
         Self(Target::Range(range))
     }
 
@@ -518,51 +809,7 @@ impl Delete {
     where
         R: RangeBounds<SongPosition>,
     {
-        Self(Target::Range(SongRange::new(range)))
-    }
-}
-
-impl SongRange {
-    fn new_usize<R: RangeBounds<usize>>(range: R) -> Self {
-        let from = match range.start_bound() {
-            Bound::Excluded(pos) => pos + 1,
-            Bound::Included(pos) => *pos,
-            Bound::Unbounded => 0,
-        };
-
-        let to = match range.end_bound() {
-            Bound::Excluded(pos) => Some(*pos),
-            Bound::Included(pos) => Some(pos + 1),
-            Bound::Unbounded => None,
-        };
-
-        Self { from, to }
-    }
-
-    fn new<R: RangeBounds<SongPosition>>(range: R) -> Self {
-        let from = match range.start_bound() {
-            Bound::Excluded(pos) => Bound::Excluded(pos.0),
-            Bound::Included(pos) => Bound::Included(pos.0),
-            Bound::Unbounded => Bound::Unbounded,
-        };
-
-        let to = match range.end_bound() {
-            Bound::Excluded(pos) => Bound::Excluded(pos.0),
-            Bound::Included(pos) => Bound::Included(pos.0),
-            Bound::Unbounded => Bound::Unbounded,
-        };
-
-        Self::new_usize((from, to))
-    }
-}
-
-impl Argument for SongRange {
-    fn render(&self, buf: &mut BytesMut) {
-        if let Some(to) = self.to {
-            write!(buf, "{}:{}", self.from, to).unwrap();
-        } else {
-            write!(buf, "{}:", self.from).unwrap();
-        }
+        Self(Target::Range(Window::from_positions(range)))
     }
 }
 
@@ -596,7 +843,7 @@ impl Move {
 
     /// Move the song at the given position.
     pub fn position(position: SongPosition) -> MoveBuilder {
-        MoveBuilder(Target::Range(SongRange::new(position..=position)))
+        MoveBuilder(Target::Range(Window::from_positions(position..=position)))
     }
 
     /// Move the given range of song positions.
@@ -612,7 +859,7 @@ impl Move {
             panic!("move commands must not have an open end");
         }
 
-        MoveBuilder(Target::Range(SongRange::new(range)))
+        MoveBuilder(Target::Range(Window::from_positions(range)))
     }
 }
 
@@ -670,80 +917,253 @@ impl Command for Move {
     }
 }
 
-/// `find` command.
+/// Sort order for the results of [`Find`] and [`FindAdd`].
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Find {
-    filter: Filter,
-    sort: Option<Tag>,
-    window: Option<SongRange>,
+struct Sort {
+    by: SortBy,
+    descending: bool,
 }
 
-impl Find {
-    /// Find all songs matching `filter`.
-    pub fn new(filter: Filter) -> Self {
-        Self {
-            filter,
-            sort: None,
-            window: None,
-        }
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SortBy {
+    Tag(Tag),
+    LastModified,
+    Added,
+}
+
+impl Sort {
+    fn render(&self, command: &mut RawCommand) {
+        let by = match &self.by {
+            SortBy::Tag(tag) => tag.as_str(),
+            SortBy::LastModified => "Last-Modified".into(),
+            SortBy::Added => "Added".into(),
+        };
+
+        let value = if self.descending {
+            format!("-{by}")
+        } else {
+            by.into_owned()
+        };
+
+        command.add_argument("sort").unwrap();
+        command.add_argument(value).expect("Invalid sort value");
     }
+}
 
-    /// Sort the result by the given tag.
-    ///
-    /// This does some special-casing for certain tags, see the [MPD documentation][0] for details.
-    ///
-    /// # Panics
-    ///
-    /// This will panic when sending the command if you pass a malformed value using the
-    /// [`Other`][error] variant.
-    ///
-    /// [0]: https://www.musicpd.org/doc/html/protocol.html#command-find
-    /// [error]: crate::tag::Tag::Other
-    pub fn sort(mut self, sort_by: Tag) -> Self {
-        self.sort = Some(sort_by);
-        self
+/// Render the arguments shared by the `find`/`search` family of commands.
+fn song_query(
+    command: &'static str,
+    filter: &Filter,
+    sort: &Option<Sort>,
+    window: Option<Window>,
+) -> RawCommand {
+    let mut command = RawCommand::new(command).argument(filter);
+
+    if let Some(sort) = sort {
+        sort.render(&mut command);
     }
 
-    /// Limit the result to the given window.
-    pub fn window<R>(mut self, window: R) -> Self
-    where
-        R: RangeBounds<usize>,
-    {
-        self.window = Some(SongRange::new_usize(window));
-        self
+    if let Some(window) = window {
+        command.add_argument("window").unwrap();
+        command.add_argument(window).unwrap();
     }
+
+    command
 }
 
-impl Command for Find {
-    type Response = Vec<res::Song>;
+macro_rules! song_query_builder {
+    ($name:ident, $doc:literal $(, $extra:ident)*) => {
+        impl $name {
+            #[doc = $doc]
+            pub fn new(filter: Filter) -> Self {
+                Self {
+                    filter,
+                    ignore_case: false,
+                    sort: None,
+                    window: None,
+                    $($extra: None,)*
+                }
+            }
 
-    fn command(&self) -> RawCommand {
-        let mut command = RawCommand::new("find").argument(&self.filter);
+            /// Compare tag values in the filter case-insensitively.
+            ///
+            /// This uses the `search` variant of the command, instead of `find`.
+            pub fn ignore_case(mut self) -> Self {
+                self.ignore_case = true;
+                self
+            }
 
-        if let Some(sort) = &self.sort {
-            command.add_argument("sort").unwrap();
-            command
-                .add_argument(sort.as_str())
-                .expect("Invalid sort value");
-        }
+            /// Sort the result by the given tag.
+            ///
+            /// This does some special-casing for certain tags, see the [MPD documentation][0] for
+            /// details.
+            ///
+            /// # Panics
+            ///
+            /// This will panic when sending the command if you pass a malformed value using the
+            /// [`Other`][error] variant.
+            ///
+            /// [0]: https://www.musicpd.org/doc/html/protocol.html#command-find
+            /// [error]: crate::tag::Tag::Other
+            pub fn sort(self, sort_by: Tag) -> Self {
+                self.sorted(SortBy::Tag(sort_by), false)
+            }
 
-        if let Some(window) = self.window {
-            command.add_argument("window").unwrap();
-            command.add_argument(window).unwrap();
-        }
+            /// Sort the result by the given tag, in descending order.
+            ///
+            /// # Panics
+            ///
+            /// See [`sort`](Self::sort).
+            pub fn sort_descending(self, sort_by: Tag) -> Self {
+                self.sorted(SortBy::Tag(sort_by), true)
+            }
 
-        command
-    }
+            /// Sort the result by the modification time of the songs, oldest first.
+            pub fn sort_by_last_modified(self) -> Self {
+                self.sorted(SortBy::LastModified, false)
+            }
 
-    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
-        res::Song::from_frame_multi(frame)
-    }
+            /// Sort the result by the modification time of the songs, newest first.
+            pub fn sort_by_last_modified_descending(self) -> Self {
+                self.sorted(SortBy::LastModified, true)
+            }
+
+            /// Sort the result by the time the songs were added to the database, oldest first.
+            ///
+            /// **NOTE**: Supported on protocol versions 0.24 and later.
+            pub fn sort_by_added(self) -> Self {
+                self.sorted(SortBy::Added, false)
+            }
+
+            /// Sort the result by the time the songs were added to the database, newest first.
+            ///
+            /// **NOTE**: Supported on protocol versions 0.24 and later.
+            pub fn sort_by_added_descending(self) -> Self {
+                self.sorted(SortBy::Added, true)
+            }
+
+            fn sorted(mut self, by: SortBy, descending: bool) -> Self {
+                self.sort = Some(Sort { by, descending });
+                self
+            }
+
+            /// Limit the result to the given window.
+            pub fn window<R>(mut self, window: R) -> Self
+            where
+                R: RangeBounds<usize>,
+            {
+                self.window = Some(Window::from_range(window));
+                self
+            }
+        }
+    };
 }
 
-/// `list` command.
+/// `find` and `search` commands.
+///
+/// By default, this uses `find`, which compares tag values exactly. Use [`Find::ignore_case`]
+/// to compare them case-insensitively instead.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct List<const N: usize = 0> {
-    tag: Tag,
+pub struct Find {
+    filter: Filter,
+    ignore_case: bool,
+    sort: Option<Sort>,
+    window: Option<Window>,
+}
+
+song_query_builder!(Find, "Find all songs matching `filter`.");
+
+impl Command for Find {
+    type Response = Vec<res::Song>;
+
+    fn command(&self) -> RawCommand {
+        let command = if self.ignore_case { "search" } else { "find" };
+        song_query(command, &self.filter, &self.sort, self.window)
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::Song::from_frame_multi(frame)
+    }
+}
+
+/// `findadd` and `searchadd` commands.
+///
+/// Add all songs matching a filter to the queue. If neither of [`FindAdd::at`],
+/// [`FindAdd::before_current`], or [`FindAdd::after_current`] is used, the songs will be appended
+/// to the queue.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FindAdd {
+    filter: Filter,
+    ignore_case: bool,
+    sort: Option<Sort>,
+    window: Option<Window>,
+    position: Option<PositionOrRelative>,
+}
+
+song_query_builder!(
+    FindAdd,
+    "Add all songs matching `filter` to the queue.",
+    position
+);
+
+impl FindAdd {
+    /// Add the songs at the given position in the queue.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.5.
+    pub fn at<P: Into<SongPosition>>(mut self, position: P) -> Self {
+        self.position = Some(PositionOrRelative::Absolute(position.into()));
+        self
+    }
+
+    /// Add the songs `delta` positions before the current song.
+    ///
+    /// A `delta` of 0 is immediately before the current song.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.5.
+    pub fn before_current(mut self, delta: usize) -> Self {
+        self.position = Some(PositionOrRelative::BeforeCurrent(delta));
+        self
+    }
+
+    /// Add the songs `delta` positions after the current song.
+    ///
+    /// A `delta` of 0 is immediately after the current song.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.5.
+    pub fn after_current(mut self, delta: usize) -> Self {
+        self.position = Some(PositionOrRelative::AfterCurrent(delta));
+        self
+    }
+}
+
+impl Command for FindAdd {
+    type Response = ();
+
+    fn command(&self) -> RawCommand {
+        let command = if self.ignore_case {
+            "searchadd"
+        } else {
+            "findadd"
+        };
+        let mut command = song_query(command, &self.filter, &self.sort, self.window);
+
+        if let Some(position) = &self.position {
+            command.add_argument("position").unwrap();
+            command.add_argument(position).unwrap();
+        }
+
+        command
+    }
+
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
+    }
+}
+
+/// `list` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct List<const N: usize = 0> {
+    tag: Tag,
     filter: Option<Filter>,
     group_by: [Tag; N],
 }
@@ -908,16 +1328,24 @@ impl<'a> Command for RenamePlaylist<'a> {
 }
 
 /// `load` command.
+///
+/// If neither of [`LoadPlaylist::at`], [`LoadPlaylist::before_current`], or
+/// [`LoadPlaylist::after_current`] is used, the songs will be appended to the queue.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LoadPlaylist<'a> {
     name: &'a str,
-    range: Option<SongRange>,
+    range: Option<Window>,
+    position: Option<PositionOrRelative>,
 }
 
 impl<'a> LoadPlaylist<'a> {
     /// Load the playlist with the given name into the queue.
     pub fn name(name: &'a str) -> Self {
-        Self { name, range: None }
+        Self {
+            name,
+            range: None,
+            position: None,
+        }
     }
 
     /// Limit the loaded playlist to the given window.
@@ -925,7 +1353,35 @@ impl<'a> LoadPlaylist<'a> {
     where
         R: RangeBounds<usize>,
     {
-        self.range = Some(SongRange::new_usize(range));
+        self.range = Some(Window::from_range(range));
+        self
+    }
+
+    /// Insert the songs at the given position in the queue.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.1.
+    pub fn at<P: Into<SongPosition>>(mut self, position: P) -> Self {
+        self.position = Some(PositionOrRelative::Absolute(position.into()));
+        self
+    }
+
+    /// Insert the songs `delta` positions before the current song.
+    ///
+    /// A `delta` of 0 is immediately before the current song.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.1.
+    pub fn before_current(mut self, delta: usize) -> Self {
+        self.position = Some(PositionOrRelative::BeforeCurrent(delta));
+        self
+    }
+
+    /// Insert the songs `delta` positions after the current song.
+    ///
+    /// A `delta` of 0 is immediately after the current song.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.1.
+    pub fn after_current(mut self, delta: usize) -> Self {
+        self.position = Some(PositionOrRelative::AfterCurrent(delta));
         self
     }
 }
@@ -936,10 +1392,63 @@ impl<'a> Command for LoadPlaylist<'a> {
     fn command(&self) -> RawCommand {
         let mut command = RawCommand::new("load").argument(self.name);
 
-        if let Some(range) = self.range {
+        // The position is only recognized after a range, so load the entire playlist if none was
+        // given
+        let range = match (self.range, self.position) {
+            (None, Some(_)) => Some(Window::from_range(..)),
+            (range, _) => range,
+        };
+
+        if let Some(range) = range {
             command.add_argument(range).unwrap();
         }
 
+        if let Some(position) = self.position {
+            command.add_argument(position).unwrap();
+        }
+
+        command
+    }
+
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
+    }
+}
+
+/// `save` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SaveQueueAsPlaylist<'a> {
+    name: &'a str,
+    mode: Option<SaveMode>,
+}
+
+impl<'a> SaveQueueAsPlaylist<'a> {
+    /// Save the queue as a new playlist with the given name.
+    pub fn name(name: &'a str) -> Self {
+        Self { name, mode: None }
+    }
+
+    /// Set the mode for saving the playlist.
+    ///
+    /// If this is not used, the server fails if a playlist with the given name already exists.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.24.
+    pub fn mode(mut self, mode: SaveMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+}
+
+impl<'a> Command for SaveQueueAsPlaylist<'a> {
+    type Response = ();
+
+    fn command(&self) -> RawCommand {
+        let mut command = RawCommand::new("save").argument(self.name);
+
+        if let Some(mode) = self.mode {
+            command.add_argument(mode.as_str()).unwrap();
+        }
+
         command
     }
 
@@ -1007,7 +1516,7 @@ pub struct RemoveFromPlaylist<'a> {
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum PositionOrRange {
     Position(usize),
-    Range(SongRange),
+    Range(Window),
 }
 
 impl<'a> RemoveFromPlaylist<'a> {
@@ -1026,7 +1535,7 @@ impl<'a> RemoveFromPlaylist<'a> {
     {
         RemoveFromPlaylist {
             playlist,
-            target: PositionOrRange::Range(SongRange::new(range)),
+            target: PositionOrRange::Range(Window::from_positions(range)),
         }
     }
 }
@@ -1162,7 +1671,7 @@ impl<'a> Command for AlbumArt<'a> {
     }
 
     fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
-        res::AlbumArt::from_frame(frame)
+        res::AlbumArt::from_frame(frame, self.offset == 0)
     }
 }
 
@@ -1195,7 +1704,7 @@ impl<'a> Command for AlbumArtEmbedded<'a> {
     }
 
     fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
-        res::AlbumArt::from_frame(frame)
+        res::AlbumArt::from_frame(frame, self.offset == 0)
     }
 }
 
@@ -1288,6 +1797,14 @@ impl<'a> StickerGet<'a> {
     pub fn new(uri: &'a str, name: &'a str) -> Self {
         Self { uri, name }
     }
+
+    /// Parse the sticker value into `T`.
+    pub fn parse_as<T>(self) -> StickerGetAs<'a, T> {
+        StickerGetAs {
+            inner: self,
+            _value: PhantomData,
+        }
+    }
 }
 
 impl<'a> Command for StickerGet<'a> {
@@ -1342,13 +1859,21 @@ impl<'a> Command for StickerSet<'a> {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StickerDelete<'a> {
     uri: &'a str,
-    name: &'a str,
+    name: Option<&'a str>,
 }
 
 impl<'a> StickerDelete<'a> {
     /// Delete the sticker `name` for the song at `uri`
     pub fn new(uri: &'a str, name: &'a str) -> Self {
-        Self { uri, name }
+        Self {
+            uri,
+            name: Some(name),
+        }
+    }
+
+    /// Delete all stickers for the song at `uri`
+    pub fn all(uri: &'a str) -> Self {
+        Self { uri, name: None }
     }
 }
 
@@ -1356,11 +1881,16 @@ impl<'a> Command for StickerDelete<'a> {
     type Response = ();
 
     fn command(&self) -> RawCommand {
-        RawCommand::new("sticker")
+        let mut command = RawCommand::new("sticker")
             .argument("delete")
             .argument("song")
-            .argument(self.uri)
-            .argument(self.name)
+            .argument(self.uri);
+
+        if let Some(name) = self.name {
+            command.add_argument(name).unwrap();
+        }
+
+        command
     }
 
     fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
@@ -1448,6 +1978,14 @@ impl<'a> StickerFind<'a> {
             filter: Some((operator, value)),
         }
     }
+
+    /// Parse the sticker values into `T`.
+    pub fn parse_as<T>(self) -> StickerFindAs<'a, T> {
+        StickerFindAs {
+            inner: self,
+            _value: PhantomData,
+        }
+    }
 }
 
 impl<'a> Command for StickerFind<'a> {
@@ -1476,7 +2014,123 @@ impl<'a> Command for StickerFind<'a> {
     }
 }
 
+/// `sticker get` command, parsing the value into `T`.
+///
+/// Created using [`StickerGetAs::new`] or [`StickerGet::parse_as`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StickerGetAs<'a, T> {
+    inner: StickerGet<'a>,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> StickerGetAs<'a, T> {
+    /// Get the sticker `name` for the song at `uri`
+    pub fn new(uri: &'a str, name: &'a str) -> Self {
+        StickerGet::new(uri, name).parse_as()
+    }
+}
+
+impl<'a, T> Command for StickerGetAs<'a, T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Response = T;
+
+    fn command(&self) -> RawCommand {
+        self.inner.command()
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        let value = res::StickerGet::from_frame(frame)?.value;
+        parse_sticker_value(value)
+    }
+}
+
+/// `sticker find` command, parsing the values into `T`.
+///
+/// Created using [`StickerFind::parse_as`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StickerFindAs<'a, T> {
+    inner: StickerFind<'a>,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> Command for StickerFindAs<'a, T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Response = Vec<res::Sticker<T>>;
+
+    fn command(&self) -> RawCommand {
+        self.inner.command()
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::StickerFind::from_frame(frame)?
+            .stickers
+            .into_iter()
+            .map(res::Sticker::parse)
+            .collect()
+    }
+}
+
+fn parse_sticker_value<T>(value: String) -> Result<T, TypedResponseError>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value
+        .parse()
+        .map_err(|e| TypedResponseError::invalid_value("sticker", value).source(e))
+}
+
+/// `stickernames` command.
+///
+/// Returns the names of all stickers in the database, regardless of the objects they are
+/// attached to.
+///
+/// **NOTE**: Supported on protocol versions later than 0.24.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StickerNames;
+
+impl Command for StickerNames {
+    type Response = Vec<String>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("stickernames")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        parse_string_list(frame, "name")
+    }
+}
+
+/// `stickertypes` command.
+///
+/// Returns the types of objects stickers can be attached to (e.g. `song` or `playlist`).
+///
+/// **NOTE**: Supported on protocol versions later than 0.24.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StickerTypes;
+
+impl Command for StickerTypes {
+    type Response = Vec<String>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("stickertypes")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        parse_string_list(frame, "stickertype")
+    }
+}
+
 /// `update` command.
+///
+/// The response is a handle to the started job, which can be used to [wait for it to
+/// finish][res::UpdateJob::finished].
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Update<'a>(Option<&'a str>);
 
@@ -1493,7 +2147,7 @@ impl<'a> Update<'a> {
 }
 
 impl<'a> Command for Update<'a> {
-    type Response = u64;
+    type Response = res::UpdateJob;
 
     fn command(&self) -> RawCommand {
         let mut command = RawCommand::new("update");
@@ -1505,8 +2159,8 @@ impl<'a> Command for Update<'a> {
         command
     }
 
-    fn response(self, mut frame: Frame) -> Result<Self::Response, TypedResponseError> {
-        value(&mut frame, "updating_db")
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::UpdateJob::from_frame(frame)
     }
 }
 
@@ -1536,7 +2190,7 @@ impl<'a> Rescan<'a> {
 }
 
 impl<'a> Command for Rescan<'a> {
-    type Response = u64;
+    type Response = res::UpdateJob;
 
     fn command(&self) -> RawCommand {
         let mut command = RawCommand::new("rescan");
@@ -1548,8 +2202,8 @@ impl<'a> Command for Rescan<'a> {
         command
     }
 
-    fn response(self, mut frame: Frame) -> Result<Self::Response, TypedResponseError> {
-        value(&mut frame, "updating_db")
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::UpdateJob::from_frame(frame)
     }
 }
 
@@ -1559,108 +2213,499 @@ impl<'a> Default for Rescan<'a> {
     }
 }
 
-/// `readmessage` command.
+/// `outputs` command.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub struct ReadChannelMessages;
+pub struct Outputs;
 
-impl Command for ReadChannelMessages {
-    type Response = Vec<(String, String)>;
+impl Command for Outputs {
+    type Response = Vec<res::Output>;
 
     fn command(&self) -> RawCommand {
-        RawCommand::new("readmessages")
+        RawCommand::new("outputs")
     }
 
     fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
-        res::parse_channel_messages(frame)
+        Vec::from_frame(frame)
     }
 }
 
-/// `channels` command.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub struct ListChannels;
+/// `enableoutput` command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnableOutput(pub u32);
 
-impl Command for ListChannels {
-    type Response = Vec<String>;
+impl Command for EnableOutput {
+    type Response = ();
 
     fn command(&self) -> RawCommand {
-        RawCommand::new("channels")
+        RawCommand::new("enableoutput").argument(self.0.to_string())
     }
 
-    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
-        let mut response = Vec::with_capacity(frame.fields_len());
-        for (key, value) in frame {
-            if &*key != "channel" {
-                return Err(TypedResponseError::unexpected_field("channel", &*key));
-            }
-
-            response.push(value);
-        }
-
-        Ok(response)
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
     }
 }
 
-/// `sendmessage` command.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SendChannelMessage<'a> {
-    channel: &'a str,
-    message: &'a str,
-}
-
-impl<'a> SendChannelMessage<'a> {
-    /// Send the given message to the given channel.
-    pub fn new(channel: &'a str, message: &'a str) -> SendChannelMessage<'a> {
-        SendChannelMessage { channel, message }
-    }
-}
+/// `disableoutput` command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DisableOutput(pub u32);
 
-impl<'a> Command for SendChannelMessage<'a> {
+impl Command for DisableOutput {
     type Response = ();
 
     fn command(&self) -> RawCommand {
-        RawCommand::new("sendmessage")
-            .argument(self.channel)
-            .argument(self.message)
+        RawCommand::new("disableoutput").argument(self.0.to_string())
     }
 
-    fn response(self, _frame: Frame) -> Result<Self::Response, TypedResponseError> {
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
         Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
+/// `readcomments` command.
+///
+/// Reads the comments (raw metadata) of the file with the given URI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadComments<'a>(pub &'a str);
+
+impl<'a> Command for ReadComments<'a> {
+    type Response = res::Comments;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("readcomments").argument(self.0)
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::Comments::from_frame(frame)
+    }
+}
+
+/// `lsinfo` command.
+///
+/// Lists the songs, subdirectories and playlists in a directory of the database.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LsInfo<'a>(Option<&'a str>);
+
+impl<'a> LsInfo<'a> {
+    /// List the root of the database.
+    pub fn new() -> Self {
+        LsInfo(None)
+    }
+
+    /// List the directory with the given path instead.
+    pub fn uri(self, uri: &'a str) -> Self {
+        Self(Some(uri))
+    }
+}
+
+impl<'a> Command for LsInfo<'a> {
+    type Response = Vec<res::DirectoryEntry>;
+
+    fn command(&self) -> RawCommand {
+        let mut command = RawCommand::new("lsinfo");
+
+        if let Some(uri) = self.0 {
+            command.add_argument(uri).unwrap();
+        }
+
+        command
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::DirectoryEntry::from_frame_multi(frame, true)
+    }
+}
+
+/// `listfiles` command.
+///
+/// Lists the files and subdirectories in a directory, including files which are not songs in the
+/// database.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListFiles<'a>(Option<&'a str>);
+
+impl<'a> ListFiles<'a> {
+    /// List the root of the music directory.
+    pub fn new() -> Self {
+        ListFiles(None)
+    }
+
+    /// List the directory with the given path instead.
+    pub fn uri(self, uri: &'a str) -> Self {
+        Self(Some(uri))
+    }
+}
+
+impl<'a> Command for ListFiles<'a> {
+    type Response = Vec<res::DirectoryEntry>;
+
+    fn command(&self) -> RawCommand {
+        let mut command = RawCommand::new("listfiles");
+
+        if let Some(uri) = self.0 {
+            command.add_argument(uri).unwrap();
+        }
+
+        command
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::DirectoryEntry::from_frame_multi(frame, false)
+    }
+}
+
+/// `readmessage` command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReadChannelMessages;
+
+impl Command for ReadChannelMessages {
+    type Response = Vec<(String, String)>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("readmessages")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::parse_channel_messages(frame)
+    }
+}
+
+/// `channels` command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListChannels;
+
+impl Command for ListChannels {
+    type Response = Vec<String>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("channels")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        let mut response = Vec::with_capacity(frame.fields_len());
+        for (key, value) in frame {
+            if &*key != "channel" {
+                return Err(TypedResponseError::unexpected_field("channel", &*key));
+            }
+
+            response.push(value);
+        }
+
+        Ok(response)
+    }
+}
+
+/// `sendmessage` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendChannelMessage<'a> {
+    channel: &'a str,
+    message: &'a str,
+}
+
+impl<'a> SendChannelMessage<'a> {
+    /// Send the given message to the given channel.
+    pub fn new(channel: &'a str, message: &'a str) -> SendChannelMessage<'a> {
+        SendChannelMessage { channel, message }
+    }
+}
+
+impl<'a> Command for SendChannelMessage<'a> {
+    type Response = ();
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("sendmessage")
+            .argument(self.channel)
+            .argument(self.message)
+    }
+
+    fn response(self, _frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
+    }
+}
+
+/// `commands` command.
+///
+/// Returns the names of the commands the current connection is permitted to use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AvailableCommands;
+
+impl Command for AvailableCommands {
+    type Response = Vec<String>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("commands")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        parse_string_list(frame, "command")
+    }
+}
+
+/// `notcommands` command.
+///
+/// Returns the names of the commands the current connection is denied, e.g. because a password
+/// granting the required permissions was not sent.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnavailableCommands;
+
+impl Command for UnavailableCommands {
+    type Response = Vec<String>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("notcommands")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        parse_string_list(frame, "command")
+    }
+}
+
+/// Parse a response consisting only of `field` entries.
+fn parse_string_list(frame: Frame, field: &'static str) -> Result<Vec<String>, TypedResponseError> {
+    let mut out = Vec::with_capacity(frame.fields_len());
+    for (key, value) in frame {
+        if &*key != field {
+            return Err(TypedResponseError::unexpected_field(field, &*key));
+        }
+
+        out.push(value);
+    }
+
+    Ok(out)
+}
+
+/// `protocol` command.
+///
+/// Returns the names of the protocol features enabled on the current connection.
+///
+/// **NOTE**: Supported on protocol versions later than 0.24.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GetProtocolFeatures;
+
+impl Command for GetProtocolFeatures {
+    type Response = Vec<String>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("protocol")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        parse_string_list(frame, "feature")
+    }
+}
+
+/// `protocol available` command.
+///
+/// Returns the names of all protocol features the server supports.
+///
+/// **NOTE**: Supported on protocol versions later than 0.24.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AvailableProtocolFeatures;
+
+impl Command for AvailableProtocolFeatures {
+    type Response = Vec<String>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("protocol").argument("available")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        parse_string_list(frame, "feature")
+    }
+}
+
+/// Manage enabled protocol features.
+///
+/// **NOTE**: Supported on protocol versions later than 0.24.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolFeatures<'a>(ProtocolFeaturesAction<'a>);
+
+impl<'a> ProtocolFeatures<'a> {
+    /// Enable all features.
+    pub fn enable_all() -> ProtocolFeatures<'static> {
+        ProtocolFeatures(ProtocolFeaturesAction::EnableAll)
+    }
+
+    /// Disable all features.
+    pub fn disable_all() -> ProtocolFeatures<'static> {
+        ProtocolFeatures(ProtocolFeaturesAction::Clear)
+    }
+
+    /// Disable the given list of features.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with an empty list of features.
+    pub fn disable(features: &'a [&'a str]) -> ProtocolFeatures<'a> {
+        assert_ne!(features.len(), 0, "The list of features must not be empty");
+        ProtocolFeatures(ProtocolFeaturesAction::Disable(features))
+    }
+
+    /// Enable the given list of features.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with an empty list of features.
+    pub fn enable(features: &'a [&'a str]) -> ProtocolFeatures<'a> {
+        assert_ne!(features.len(), 0, "The list of features must not be empty");
+        ProtocolFeatures(ProtocolFeaturesAction::Enable(features))
+    }
+}
+
+impl<'a> Command for ProtocolFeatures<'a> {
+    type Response = ();
+
+    fn command(&self) -> RawCommand {
+        let mut cmd = RawCommand::new("protocol");
+
+        match &self.0 {
+            ProtocolFeaturesAction::EnableAll => cmd.add_argument("all").unwrap(),
+            ProtocolFeaturesAction::Clear => cmd.add_argument("clear").unwrap(),
+            ProtocolFeaturesAction::Disable(features) => {
+                cmd.add_argument("disable").unwrap();
+
+                for feature in features.iter() {
+                    cmd.add_argument(*feature).unwrap();
+                }
+            }
+            ProtocolFeaturesAction::Enable(features) => {
+                cmd.add_argument("enable").unwrap();
+
+                for feature in features.iter() {
+                    cmd.add_argument(*feature).unwrap();
+                }
+            }
+        }
+
+        cmd
+    }
+
+    fn response(self, _: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ProtocolFeaturesAction<'a> {
+    EnableAll,
+    Clear,
+    Disable(&'a [&'a str]),
+    Enable(&'a [&'a str]),
+}
+
+/// `config` command.
+///
+/// This command is only available to clients connected via a local socket.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Config;
+
+impl Command for Config {
+    type Response = res::Config;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("config")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        res::Config::from_frame(frame)
+    }
+}
+
+/// `decoders` command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Decoders;
+
+impl Command for Decoders {
+    type Response = Vec<res::Decoder>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("decoders")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        Vec::from_frame(frame)
+    }
+}
+
+/// `listneighbors` command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListNeighbors;
+
+impl Command for ListNeighbors {
+    type Response = Vec<res::Neighbor>;
+
+    fn command(&self) -> RawCommand {
+        RawCommand::new("listneighbors")
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        Vec::from_frame(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
 
     #[test]
     fn range_arg() {
         let mut buf = BytesMut::new();
 
-        SongRange::new_usize(2..4).render(&mut buf);
+        Window::from_range(2..4).render(&mut buf);
         assert_eq!(buf, "2:4");
         buf.clear();
 
-        SongRange::new_usize(3..).render(&mut buf);
+        Window::from_range(3..).render(&mut buf);
         assert_eq!(buf, "3:");
         buf.clear();
 
-        SongRange::new_usize(2..=5).render(&mut buf);
+        Window::from_range(2..=5).render(&mut buf);
         assert_eq!(buf, "2:6");
         buf.clear();
 
-        SongRange::new_usize(..5).render(&mut buf);
+        Window::from_range(..5).render(&mut buf);
         assert_eq!(buf, "0:5");
         buf.clear();
 
-        SongRange::new_usize(..).render(&mut buf);
+        Window::from_range(..).render(&mut buf);
         assert_eq!(buf, "0:");
         buf.clear();
 
-        SongRange::new_usize(1..=1).render(&mut buf);
+        Window::from_range(1..=1).render(&mut buf);
         assert_eq!(buf, "1:2");
         buf.clear();
     }
 
+    #[test]
+    fn command_mixramp() {
+        assert_eq!(
+            MixRampDb(-17.5).command(),
+            RawCommand::new("mixrampdb").argument("-17.5")
+        );
+        assert_eq!(
+            MixRampDelay(Some(Duration::from_secs(2))).command(),
+            RawCommand::new("mixrampdelay").argument("2.000")
+        );
+        assert_eq!(
+            MixRampDelay(None).command(),
+            RawCommand::new("mixrampdelay").argument("nan")
+        );
+    }
+
+    #[test]
+    fn command_replay_gain() {
+        assert_eq!(
+            ReplayGainStatus.command(),
+            RawCommand::new("replay_gain_status")
+        );
+        assert_eq!(
+            SetReplayGainMode(ReplayGainMode::Off).command(),
+            RawCommand::new("replay_gain_mode").argument("off")
+        );
+        assert_eq!(
+            SetReplayGainMode(ReplayGainMode::Auto).command(),
+            RawCommand::new("replay_gain_mode").argument("auto")
+        );
+    }
+
     #[test]
     fn command_crossfade() {
         assert_eq!(
@@ -1677,6 +2722,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_getplaylisturis() {
+        assert_eq!(
+            GetPlaylistUris("foo").command(),
+            RawCommand::new("listplaylist").argument("foo")
+        );
+    }
+
+    #[test]
+    fn command_window() {
+        assert_eq!(
+            Queue.window(Window::first(10)).command(),
+            RawCommand::new("playlistinfo").argument("0:10")
+        );
+
+        assert_eq!(
+            GetPlaylist("foo").window(5..).command(),
+            RawCommand::new("listplaylistinfo")
+                .argument("foo")
+                .argument("5:")
+        );
+
+        assert_eq!(
+            Find::new(Filter::tag(Tag::Artist, "Foo"))
+                .window(Window::new(20, 10))
+                .command(),
+            RawCommand::new("find")
+                .argument(Filter::tag(Tag::Artist, "Foo"))
+                .argument("window")
+                .argument("20:30")
+        );
+    }
+
+    #[test]
+    fn command_queue_entry() {
+        assert_eq!(
+            Queue.song(SongPosition(3)).command(),
+            RawCommand::new("playlistinfo").argument("3")
+        );
+        assert_eq!(
+            Queue.song(SongId(12)).command(),
+            RawCommand::new("playlistid").argument("12")
+        );
+    }
+
+    #[test]
+    fn command_queue_changes() {
+        assert_eq!(
+            QueueChanges(42).command(),
+            RawCommand::new("plchanges").argument("42")
+        );
+    }
+
+    #[test]
+    fn window_advance() {
+        let window = Window::new(10, 10);
+
+        assert_eq!(window.limit(), Some(10));
+        assert_eq!(window.advance(10), Some(Window::new(20, 10)));
+        assert_eq!(window.advance(3), None);
+        assert_eq!(Window::from_range(5..).advance(100), None);
+        assert_eq!(Window::first(0).advance(0), None);
+    }
+
     #[test]
     fn command_volume() {
         assert_eq!(
@@ -1685,6 +2794,11 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_get_volume() {
+        assert_eq!(GetVolume.command(), RawCommand::new("getvol"));
+    }
+
     #[test]
     fn command_seek_to() {
         let duration = Duration::from_secs(2);
@@ -1704,6 +2818,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_seek_to_fractional() {
+        assert_eq!(
+            SeekTo(SongId(7).into(), Duration::from_millis(61_250)).command(),
+            RawCommand::new("seekid").argument("7").argument("61.250")
+        );
+    }
+
     #[test]
     fn command_seek() {
         let duration = Duration::from_secs(1);
@@ -1757,6 +2879,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn local_file_uri() {
+        let path = std::env::current_exe().unwrap();
+        let uri = Add::local_file_uri(&path).unwrap();
+
+        assert!(uri.starts_with("file:///"));
+        assert!(uri.ends_with(path.file_name().unwrap().to_str().unwrap()));
+
+        let dir = path.parent().unwrap();
+        assert_eq!(
+            Add::local_file_uri(dir).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            Add::local_file_uri(Path::new("foo/bar.mp3"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            Add::local_file_uri(&dir.join("does-not-exist.mp3"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
     #[test]
     fn command_delete() {
         assert_eq!(
@@ -1811,6 +2960,133 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_set_consume() {
+        assert_eq!(
+            SetConsume(ConsumeMode::Enabled).command(),
+            RawCommand::new("consume").argument("1")
+        );
+        assert_eq!(
+            SetConsume(ConsumeMode::Oneshot).command(),
+            RawCommand::new("consume").argument("oneshot")
+        );
+    }
+
+    #[test]
+    fn command_load_playlist() {
+        assert_eq!(
+            LoadPlaylist::name("foo").command(),
+            RawCommand::new("load").argument("foo")
+        );
+
+        assert_eq!(
+            LoadPlaylist::name("foo").range(2..5).command(),
+            RawCommand::new("load").argument("foo").argument("2:5")
+        );
+
+        assert_eq!(
+            LoadPlaylist::name("foo")
+                .range(2..)
+                .after_current(0)
+                .command(),
+            RawCommand::new("load")
+                .argument("foo")
+                .argument("2:")
+                .argument("+0")
+        );
+
+        assert_eq!(
+            LoadPlaylist::name("foo").at(3).command(),
+            RawCommand::new("load")
+                .argument("foo")
+                .argument("0:")
+                .argument("3")
+        );
+    }
+
+    #[test]
+    fn command_protocol() {
+        assert_eq!(GetProtocolFeatures.command(), RawCommand::new("protocol"));
+        assert_eq!(
+            AvailableProtocolFeatures.command(),
+            RawCommand::new("protocol").argument("available")
+        );
+        assert_eq!(
+            ProtocolFeatures::disable_all().command(),
+            RawCommand::new("protocol").argument("clear")
+        );
+        assert_eq!(
+            ProtocolFeatures::enable(&["hide_playlists_in_root", "foo"]).command(),
+            RawCommand::new("protocol")
+                .argument("enable")
+                .argument("hide_playlists_in_root")
+                .argument("foo")
+        );
+    }
+
+    #[test]
+    fn command_password() {
+        assert_eq!(
+            Password("foo \"bar\"").command(),
+            RawCommand::new("password").argument("foo \"bar\"")
+        );
+        assert_eq!(
+            format!("{:?}", Password("foo")),
+            r#"Password("<redacted>")"#
+        );
+    }
+
+    #[test]
+    fn command_save() {
+        assert_eq!(
+            SaveQueueAsPlaylist::name("foo").command(),
+            RawCommand::new("save").argument("foo")
+        );
+        assert_eq!(
+            SaveQueueAsPlaylist::name("foo")
+                .mode(SaveMode::Replace)
+                .command(),
+            RawCommand::new("save").argument("foo").argument("replace")
+        );
+    }
+
+    #[test]
+    fn command_find_add() {
+        let filter = Filter::tag(Tag::Album, "Foo");
+
+        assert_eq!(
+            FindAdd::new(filter.clone()).command(),
+            RawCommand::new("findadd").argument(filter.clone())
+        );
+
+        assert_eq!(
+            FindAdd::new(filter.clone())
+                .sort(Tag::Track)
+                .after_current(0)
+                .command(),
+            RawCommand::new("findadd")
+                .argument(filter.clone())
+                .argument("sort")
+                .argument("Track")
+                .argument("position")
+                .argument("+0")
+        );
+
+        assert_eq!(
+            FindAdd::new(filter.clone())
+                .ignore_case()
+                .window(..5)
+                .at(3)
+                .command(),
+            RawCommand::new("searchadd")
+                .argument(filter)
+                .argument("window")
+                .argument("0:5")
+                .argument("position")
+                .argument("3")
+        );
+    }
+
     #[test]
     fn command_find() {
         let filter = Filter::tag(Tag::Artist, "Foo");
@@ -1842,6 +3118,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_find_sort_order() {
+        let filter = Filter::tag(Tag::Artist, "Foo");
+
+        assert_eq!(
+            Find::new(filter.clone())
+                .sort_descending(Tag::Album)
+                .command(),
+            RawCommand::new("find")
+                .argument(filter.clone())
+                .argument("sort")
+                .argument("-Album")
+        );
+
+        assert_eq!(
+            Find::new(filter.clone())
+                .ignore_case()
+                .sort_by_last_modified_descending()
+                .window(..10)
+                .command(),
+            RawCommand::new("search")
+                .argument(filter.clone())
+                .argument("sort")
+                .argument("-Last-Modified")
+                .argument("window")
+                .argument("0:10")
+        );
+
+        assert_eq!(
+            Find::new(filter.clone())
+                .ignore_case()
+                .sort_by_last_modified()
+                .command(),
+            RawCommand::new("search")
+                .argument(filter)
+                .argument("sort")
+                .argument("Last-Modified")
+        );
+    }
+
+    #[test]
+    fn command_find_sort_by_added() {
+        let filter = Filter::tag(Tag::Artist, "Foo");
+
+        assert_eq!(
+            Find::new(filter.clone())
+                .sort_by_added_descending()
+                .window(..10)
+                .command(),
+            RawCommand::new("find")
+                .argument(filter)
+                .argument("sort")
+                .argument("-Added")
+                .argument("window")
+                .argument("0:10")
+        );
+    }
+
     #[test]
     fn command_list() {
         assert_eq!(
@@ -1881,6 +3215,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_lenient() {
+        assert_eq!(
+            crate::commands::Lenient(ListAllIn::root()).command(),
+            RawCommand::new("listallinfo")
+        );
+    }
+
     #[test]
     fn command_playlistdelete() {
         assert_eq!(
@@ -1969,6 +3311,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_sticker_delete_all() {
+        assert_eq!(
+            StickerDelete::all("foo").command(),
+            RawCommand::new("sticker")
+                .argument("delete")
+                .argument("song")
+                .argument("foo")
+        );
+    }
+
+    #[test]
+    fn command_sticker_parse_as() {
+        assert_eq!(
+            StickerGetAs::<u8>::new("foo", "rating").command(),
+            StickerGet::new("foo", "rating").command()
+        );
+        assert_eq!(
+            StickerFind::new("foo", "rating")
+                .where_gt("5")
+                .parse_as::<u8>()
+                .command(),
+            StickerFind::new("foo", "rating").where_gt("5").command()
+        );
+
+        assert_eq!(parse_sticker_value::<u8>(String::from("5")).unwrap(), 5);
+        assert!(parse_sticker_value::<u8>(String::from("five")).is_err());
+    }
+
     #[test]
     fn command_sticker_list() {
         assert_eq!(
@@ -2003,6 +3374,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_output_toggles() {
+        assert_eq!(
+            EnableOutput(2).command(),
+            RawCommand::new("enableoutput").argument("2")
+        );
+        assert_eq!(
+            DisableOutput(0).command(),
+            RawCommand::new("disableoutput").argument("0")
+        );
+    }
+
     #[test]
     fn command_update() {
         assert_eq!(Update::new().command(), RawCommand::new("update"));
@@ -2023,6 +3406,19 @@ mod tests {
         )
     }
 
+    #[test]
+    fn command_directory_listing() {
+        assert_eq!(LsInfo::new().command(), RawCommand::new("lsinfo"));
+        assert_eq!(
+            LsInfo::new().uri("foo/bar").command(),
+            RawCommand::new("lsinfo").argument("foo/bar")
+        );
+        assert_eq!(
+            ListFiles::new().uri("foo/bar").command(),
+            RawCommand::new("listfiles").argument("foo/bar")
+        );
+    }
+
     #[test]
     fn command_subscribe() {
         assert_eq!(
@@ -2061,4 +3457,28 @@ mod tests {
                 .argument("bar")
         );
     }
+
+    #[test]
+    fn command_available_commands() {
+        assert_eq!(AvailableCommands.command(), RawCommand::new("commands"));
+        assert_eq!(
+            UnavailableCommands.command(),
+            RawCommand::new("notcommands")
+        );
+    }
+
+    #[test]
+    fn command_decoders() {
+        assert_eq!(Decoders.command(), RawCommand::new("decoders"));
+    }
+
+    #[test]
+    fn command_config() {
+        assert_eq!(Config.command(), RawCommand::new("config"));
+    }
+
+    #[test]
+    fn command_list_neighbors() {
+        assert_eq!(ListNeighbors.command(), RawCommand::new("listneighbors"));
+    }
 }
@@ -12,7 +12,12 @@ pub mod definitions;
 
 mod command_list;
 
-use std::{fmt::Write, time::Duration};
+use std::{
+    fmt::{self, Write},
+    ops::{self, Bound, RangeBounds},
+    str::FromStr,
+    time::Duration,
+};
 
 use bytes::BytesMut;
 use mpd_protocol::{
@@ -20,10 +25,16 @@ use mpd_protocol::{
     response::Frame,
 };
 
-pub use self::{command_list::CommandList, definitions::*};
-use crate::responses::TypedResponseError;
+pub use self::{
+    command_list::{CommandList, PartialCommandList},
+    definitions::*,
+};
+use crate::responses::{FromFrameLenient, ParseEnumError, TypedResponseError};
 
 /// Stable identifier of a song in the queue.
+///
+/// The `Display` implementation renders the ID with a `#` prefix (e.g. `#12`), to distinguish it
+/// from a [`SongPosition`]. The raw value can be obtained with `u64::from`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SongId(pub u64);
 
@@ -33,6 +44,18 @@ impl From<u64> for SongId {
     }
 }
 
+impl From<SongId> for u64 {
+    fn from(id: SongId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for SongId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
 impl Argument for SongId {
     fn render(&self, buf: &mut BytesMut) {
         write!(buf, "{}", self.0).unwrap();
@@ -42,21 +65,217 @@ impl Argument for SongId {
 /// Position of a song in the queue.
 ///
 /// This will change when the queue is modified.
+///
+/// Positions can be offset by adding or subtracting a `usize`, which panics on overflow like
+/// the underlying integer arithmetic. Use [`SongPosition::checked_offset`] for relative movements
+/// that may go out of bounds.
+///
+/// ```
+/// use mpd_client::commands::SongPosition;
+///
+/// let pos = SongPosition(3);
+/// assert_eq!(pos + 2, SongPosition(5));
+/// assert_eq!(pos.checked_offset(-4), None);
+/// assert_eq!(usize::from(pos - 1), 2);
+/// assert_eq!(pos.to_string(), "3");
+/// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SongPosition(pub usize);
 
+impl SongPosition {
+    /// Offset the position by `delta`, returning `None` if the result would be negative or
+    /// overflow.
+    pub fn checked_offset(self, delta: isize) -> Option<SongPosition> {
+        self.0.checked_add_signed(delta).map(SongPosition)
+    }
+
+    /// Returns the number of positions between `self` and `other`.
+    pub fn distance(self, other: SongPosition) -> usize {
+        self.0.abs_diff(other.0)
+    }
+}
+
 impl From<usize> for SongPosition {
     fn from(pos: usize) -> Self {
         Self(pos)
     }
 }
 
+impl From<SongPosition> for usize {
+    fn from(pos: SongPosition) -> Self {
+        pos.0
+    }
+}
+
+impl ops::Add<usize> for SongPosition {
+    type Output = SongPosition;
+
+    fn add(self, rhs: usize) -> Self::Output {
+        SongPosition(self.0 + rhs)
+    }
+}
+
+impl ops::AddAssign<usize> for SongPosition {
+    fn add_assign(&mut self, rhs: usize) {
+        self.0 += rhs;
+    }
+}
+
+impl ops::Sub<usize> for SongPosition {
+    type Output = SongPosition;
+
+    fn sub(self, rhs: usize) -> Self::Output {
+        SongPosition(self.0 - rhs)
+    }
+}
+
+impl ops::SubAssign<usize> for SongPosition {
+    fn sub_assign(&mut self, rhs: usize) {
+        self.0 -= rhs;
+    }
+}
+
+impl fmt::Display for SongPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl Argument for SongPosition {
     fn render(&self, buf: &mut BytesMut) {
         write!(buf, "{}", self.0).unwrap();
     }
 }
 
+/// A window into a list of results, in the `START:END` form accepted by commands like [`Find`],
+/// [`Queue`], or [`GetPlaylist`].
+///
+/// This implements [`RangeBounds`], so it can be passed anywhere a range of result positions is
+/// accepted. When paging through a long list, use [`Window::advance`] to get the following page.
+///
+/// ```
+/// use mpd_client::commands::Window;
+///
+/// let page = Window::first(50);
+/// assert_eq!(page.advance(50), Some(Window::new(50, 50)));
+///
+/// // Fewer results than requested means the end of the list was reached
+/// assert_eq!(page.advance(23), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Window {
+    start: usize,
+    end: Option<usize>,
+}
+
+impl Window {
+    /// A window of (at most) `limit` entries, starting at `offset`.
+    pub fn new(offset: usize, limit: usize) -> Window {
+        Window {
+            start: offset,
+            end: Some(offset.saturating_add(limit)),
+        }
+    }
+
+    /// A window of the first `limit` entries.
+    pub fn first(limit: usize) -> Window {
+        Window::new(0, limit)
+    }
+
+    /// Convert an arbitrary range into a window.
+    pub fn from_range<R>(range: R) -> Window
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Excluded(pos) => pos + 1,
+            Bound::Included(pos) => *pos,
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Excluded(pos) => Some(*pos),
+            Bound::Included(pos) => Some(pos + 1),
+            Bound::Unbounded => None,
+        };
+
+        Window { start, end }
+    }
+
+    /// Convert a range of queue positions into a window.
+    pub(crate) fn from_positions<R>(range: R) -> Window
+    where
+        R: RangeBounds<SongPosition>,
+    {
+        let start = match range.start_bound() {
+            Bound::Excluded(pos) => Bound::Excluded(pos.0),
+            Bound::Included(pos) => Bound::Included(pos.0),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Excluded(pos) => Bound::Excluded(pos.0),
+            Bound::Included(pos) => Bound::Included(pos.0),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        Window::from_range((start, end))
+    }
+
+    /// The offset of the first entry in the window.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The offset one past the last entry in the window, or `None` if the window is open-ended.
+    pub fn end(&self) -> Option<usize> {
+        self.end
+    }
+
+    /// The maximum number of entries in the window, or `None` if the window is open-ended.
+    pub fn limit(&self) -> Option<usize> {
+        self.end.map(|end| end.saturating_sub(self.start))
+    }
+
+    /// Get the window immediately following this one, with the same size.
+    ///
+    /// `received` is the number of results the server returned for this window. If it is less
+    /// than the size of the window the end of the results was reached, and this returns `None`.
+    /// Open-ended windows always return `None`.
+    pub fn advance(self, received: usize) -> Option<Window> {
+        let limit = self.limit()?;
+
+        if received < limit || limit == 0 {
+            return None;
+        }
+
+        Some(Window::new(self.start.saturating_add(limit), limit))
+    }
+}
+
+impl RangeBounds<usize> for Window {
+    fn start_bound(&self) -> Bound<&usize> {
+        Bound::Included(&self.start)
+    }
+
+    fn end_bound(&self) -> Bound<&usize> {
+        match &self.end {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        }
+    }
+}
+
+impl Argument for Window {
+    fn render(&self, buf: &mut BytesMut) {
+        if let Some(end) = self.end {
+            write!(buf, "{}:{}", self.start, end).unwrap();
+        } else {
+            write!(buf, "{}:", self.start).unwrap();
+        }
+    }
+}
+
 /// Possible ways to seek in the current song.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SeekMode {
@@ -77,6 +296,121 @@ pub enum SingleMode {
     Oneshot,
 }
 
+impl SingleMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            SingleMode::Disabled => "0",
+            SingleMode::Enabled => "1",
+            SingleMode::Oneshot => "oneshot",
+        }
+    }
+}
+
+/// Parses the protocol representation of the mode (`0`, `1` or `oneshot`).
+impl FromStr for SingleMode {
+    type Err = ParseEnumError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw {
+            "0" => Ok(SingleMode::Disabled),
+            "1" => Ok(SingleMode::Enabled),
+            "oneshot" => Ok(SingleMode::Oneshot),
+            _ => Err(ParseEnumError::new("single mode", raw)),
+        }
+    }
+}
+
+/// Renders the protocol representation of the mode.
+impl fmt::Display for SingleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Possible `consume` modes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum ConsumeMode {
+    Enabled,
+    Disabled,
+    /// Disable consume mode again after the current song (supported on protocol versions 0.24
+    /// and later).
+    Oneshot,
+}
+
+impl ConsumeMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ConsumeMode::Disabled => "0",
+            ConsumeMode::Enabled => "1",
+            ConsumeMode::Oneshot => "oneshot",
+        }
+    }
+}
+
+/// Parses the protocol representation of the mode (`0`, `1` or `oneshot`).
+impl FromStr for ConsumeMode {
+    type Err = ParseEnumError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw {
+            "0" => Ok(ConsumeMode::Disabled),
+            "1" => Ok(ConsumeMode::Enabled),
+            "oneshot" => Ok(ConsumeMode::Oneshot),
+            _ => Err(ParseEnumError::new("consume mode", raw)),
+        }
+    }
+}
+
+/// Renders the protocol representation of the mode.
+impl fmt::Display for ConsumeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Possible modes for saving the queue to a stored playlist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaveMode {
+    /// Create a new playlist, failing if one with the same name already exists.
+    Create,
+    /// Append the queue to an existing playlist.
+    Append,
+    /// Replace the contents of an existing playlist.
+    Replace,
+}
+
+impl SaveMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            SaveMode::Create => "create",
+            SaveMode::Append => "append",
+            SaveMode::Replace => "replace",
+        }
+    }
+}
+
+/// Parses the protocol representation of the mode (`create`, `append` or `replace`).
+impl FromStr for SaveMode {
+    type Err = ParseEnumError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw {
+            "create" => Ok(SaveMode::Create),
+            "append" => Ok(SaveMode::Append),
+            "replace" => Ok(SaveMode::Replace),
+            _ => Err(ParseEnumError::new("save mode", raw)),
+        }
+    }
+}
+
+/// Renders the protocol representation of the mode.
+impl fmt::Display for SaveMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Possible `replay_gain_mode` modes.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(missing_docs)]
@@ -127,3 +461,101 @@ pub trait Command {
     /// This should return an error if the response was invalid.
     fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError>;
 }
+
+/// A command which has been encoded ahead of time, so it can be sent repeatedly without rendering
+/// and validating its arguments every time.
+///
+/// This is useful for commands sent very frequently, such as [`Status`] in a polling loop. A
+/// reference to a `PreparedCommand` can be sent using [`Client::command`][crate::Client::command]
+/// or as part of a command list, as long as the wrapped command is [`Clone`].
+///
+/// ```no_run
+/// use mpd_client::{
+///     commands::{PreparedCommand, Status},
+///     Client,
+/// };
+///
+/// async fn poll(client: &Client) {
+///     let status = PreparedCommand::new(Status);
+///
+///     loop {
+///         let status = client.command(&status).await.unwrap();
+///         println!("{:?}", status.elapsed);
+///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedCommand<C> {
+    command: C,
+    raw: RawCommand,
+}
+
+impl<C: Command> PreparedCommand<C> {
+    /// Encode the given command.
+    pub fn new(command: C) -> PreparedCommand<C> {
+        let raw = command.command();
+        PreparedCommand { command, raw }
+    }
+
+    /// Get the encoded raw command.
+    pub fn raw(&self) -> &RawCommand {
+        &self.raw
+    }
+
+    /// Get back the wrapped command.
+    pub fn into_inner(self) -> C {
+        self.command
+    }
+}
+
+impl<C: Command> Command for PreparedCommand<C> {
+    type Response = C::Response;
+
+    fn command(&self) -> RawCommand {
+        self.raw.clone()
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        self.command.response(frame)
+    }
+}
+
+impl<C: Command + Clone> Command for &PreparedCommand<C> {
+    type Response = C::Response;
+
+    fn command(&self) -> RawCommand {
+        self.raw.clone()
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        self.command.clone().response(frame)
+    }
+}
+
+/// Wrapper for commands which converts the response [leniently].
+///
+/// Fields that fail to convert are skipped instead of failing the entire response, which is
+/// useful for commands returning large lists of songs (like [`ListAllIn`]) where a single
+/// malformed value would otherwise make the whole list unavailable. The errors for the skipped
+/// fields are returned alongside the response.
+///
+/// [leniently]: crate::responses::FromFrameLenient
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lenient<C>(pub C);
+
+impl<C> Command for Lenient<C>
+where
+    C: Command,
+    C::Response: FromFrameLenient,
+{
+    type Response = (C::Response, Vec<TypedResponseError>);
+
+    fn command(&self) -> RawCommand {
+        self.0.command()
+    }
+
+    fn response(self, frame: Frame) -> Result<Self::Response, TypedResponseError> {
+        Ok(C::Response::from_frame_lenient(frame))
+    }
+}
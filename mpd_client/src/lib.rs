@@ -15,14 +15,31 @@
 //!
 //! # Crate Features
 //!
-//! | Feature  | Description                       |
-//! |----------|-----------------------------------|
-//! | `chrono` | Support for parsing [`Timestamp`] |
+//! | Feature     | Description                                                       |
+//! |-------------|-------------------------------------------------------------------|
+//! | `chrono`    | Support for parsing [`Timestamp`]                                 |
+//! | `recording` | Recording and replaying connections ([`Recorder`] and [`Replay`]) |
+//! | `derive`    | Derive macro for [`FromFrame`]                                    |
 //!
 //! [`Timestamp`]: responses::Timestamp
+//! [`FromFrame`]: responses::FromFrame
 
+#![cfg_attr(
+    feature = "recording",
+    doc = "[`Recorder`]: client::Recorder",
+    doc = "[`Replay`]: client::Replay"
+)]
+#![cfg_attr(
+    not(feature = "recording"),
+    doc = "[`Recorder`]: https://docs.rs/mpd_client/latest/mpd_client/client/struct.Recorder.html",
+    doc = "[`Replay`]: https://docs.rs/mpd_client/latest/mpd_client/client/struct.Replay.html"
+)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+// Allows the derive macros to refer to this crate by name in tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as mpd_client;
+
 pub mod client;
 pub mod commands;
 pub mod filter;
@@ -0,0 +1,154 @@
+use std::fmt;
+
+use crate::responses::{FromFieldValue, TypedResponseError};
+
+/// Base sample rate of DSD formats, as used by the `dsdN` shorthand.
+const DSD_BASE_RATE: u32 = 44100;
+
+/// An audio format, as reported in the `audio` field of [`Status`] and the `Format` field of
+/// songs.
+///
+/// The fields are `None` if the format contains a `*` wildcard in their place, which is only the
+/// case in configured output formats.
+///
+/// [`Status`]: crate::responses::Status
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct AudioFormat {
+    /// Sample rate in Hz.
+    ///
+    /// For DSD, this is the number of bytes per second and channel (e.g. 352800 for `dsd64`),
+    /// matching the convention MPD uses internally.
+    pub sample_rate: Option<u32>,
+    /// Format of the individual samples.
+    pub bits: Option<SampleFormat>,
+    /// Number of channels.
+    pub channels: Option<u8>,
+}
+
+/// Format of the samples in an [`AudioFormat`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SampleFormat {
+    /// Integer samples with the given number of bits.
+    Bits(u8),
+    /// 32 bit floating point samples.
+    Float,
+    /// Direct Stream Digital (1 bit samples, packed into bytes).
+    Dsd,
+}
+
+impl AudioFormat {
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let mut parts = raw.split(':');
+        let first = parts.next()?;
+
+        let format = if let Some(multiplier) = first.strip_prefix("dsd") {
+            // Shorthand form "dsd64:2"
+            let multiplier = multiplier.parse::<u32>().ok()?;
+
+            AudioFormat {
+                sample_rate: Some(multiplier.checked_mul(DSD_BASE_RATE)? / 8),
+                bits: Some(SampleFormat::Dsd),
+                channels: parse_wildcard(parts.next()?)?,
+            }
+        } else {
+            AudioFormat {
+                sample_rate: parse_wildcard(first)?,
+                bits: match parts.next()? {
+                    "*" => None,
+                    "f" => Some(SampleFormat::Float),
+                    "dsd" => Some(SampleFormat::Dsd),
+                    bits => Some(SampleFormat::Bits(bits.parse().ok()?)),
+                },
+                channels: parse_wildcard(parts.next()?)?,
+            }
+        };
+
+        match parts.next() {
+            Some(_) => None,
+            None => Some(format),
+        }
+    }
+}
+
+fn parse_wildcard<T: std::str::FromStr>(raw: &str) -> Option<Option<T>> {
+    if raw == "*" {
+        Some(None)
+    } else {
+        raw.parse().ok().map(Some)
+    }
+}
+
+impl FromFieldValue for AudioFormat {
+    fn from_value(v: String, field: &str) -> Result<Self, TypedResponseError> {
+        match AudioFormat::parse(&v) {
+            Some(format) => Ok(format),
+            None => Err(TypedResponseError::invalid_value(field, v)),
+        }
+    }
+}
+
+impl fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn wildcard<T: fmt::Display>(f: &mut fmt::Formatter<'_>, v: Option<T>) -> fmt::Result {
+            match v {
+                Some(v) => write!(f, "{v}"),
+                None => write!(f, "*"),
+            }
+        }
+
+        wildcard(f, self.sample_rate)?;
+
+        match self.bits {
+            None => write!(f, ":*:")?,
+            Some(SampleFormat::Bits(bits)) => write!(f, ":{bits}:")?,
+            Some(SampleFormat::Float) => write!(f, ":f:")?,
+            Some(SampleFormat::Dsd) => write!(f, ":dsd:")?,
+        }
+
+        wildcard(f, self.channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_format_parsing() {
+        let pcm = AudioFormat::parse("44100:16:2").unwrap();
+        assert_eq!(
+            pcm,
+            AudioFormat {
+                sample_rate: Some(44100),
+                bits: Some(SampleFormat::Bits(16)),
+                channels: Some(2),
+            }
+        );
+        assert_eq!(pcm.to_string(), "44100:16:2");
+
+        assert_eq!(
+            AudioFormat::parse("48000:f:*").unwrap(),
+            AudioFormat {
+                sample_rate: Some(48000),
+                bits: Some(SampleFormat::Float),
+                channels: None,
+            }
+        );
+
+        let dsd = AudioFormat {
+            sample_rate: Some(352800),
+            bits: Some(SampleFormat::Dsd),
+            channels: Some(2),
+        };
+        assert_eq!(AudioFormat::parse("dsd64:2").unwrap(), dsd);
+        assert_eq!(AudioFormat::parse("352800:dsd:2").unwrap(), dsd);
+
+        assert_eq!(AudioFormat::parse("*:*:*").unwrap().to_string(), "*:*:*");
+
+        assert_eq!(AudioFormat::parse(""), None);
+        assert_eq!(AudioFormat::parse("44100:16"), None);
+        assert_eq!(AudioFormat::parse("44100:16:2:1"), None);
+        assert_eq!(AudioFormat::parse("dsdfoo:2"), None);
+    }
+}
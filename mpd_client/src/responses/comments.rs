@@ -0,0 +1,73 @@
+use mpd_protocol::response::Frame;
+
+use crate::responses::{FromFrame, TypedResponseError};
+
+/// Comments (metadata) of a file, as returned by [`readcomments`].
+///
+/// Files frequently contain several comments with the same key, so this preserves all of them in
+/// the order they were returned by the server.
+///
+/// [`readcomments`]: crate::commands::definitions::ReadComments
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Comments(pub Vec<(String, String)>);
+
+impl Comments {
+    /// Get the first value for the given key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Get all values for the given key.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.0
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns an iterator over all key-value pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns `true` if the file has no comments.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromFrame for Comments {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Ok(Comments(
+            frame
+                .into_iter()
+                .map(|(key, value)| (key.as_ref().to_owned(), value))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_lookup() {
+        let comments = Comments(vec![
+            (String::from("COMMENT"), String::from("foo")),
+            (String::from("TITLE"), String::from("bar")),
+            (String::from("COMMENT"), String::from("baz")),
+        ]);
+
+        assert_eq!(comments.get("COMMENT"), Some("foo"));
+        assert_eq!(
+            comments.get_all("COMMENT").collect::<Vec<_>>(),
+            ["foo", "baz"]
+        );
+        assert_eq!(comments.get("ARTIST"), None);
+        assert_eq!(comments.iter().nth(1), Some(("TITLE", "bar")));
+    }
+}
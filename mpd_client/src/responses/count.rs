@@ -3,7 +3,7 @@ use std::time::Duration;
 use mpd_protocol::response::Frame;
 
 use crate::{
-    responses::{value, FromFieldValue, TypedResponseError},
+    responses::{value, FromFieldValue, FromFrame, TypedResponseError},
     tag::Tag,
 };
 
@@ -17,14 +17,16 @@ pub struct Count {
     pub playtime: Duration,
 }
 
-impl Count {
-    pub(crate) fn from_frame(mut frame: Frame) -> Result<Count, TypedResponseError> {
+impl FromFrame for Count {
+    fn from_frame(mut frame: Frame) -> Result<Count, TypedResponseError> {
         Ok(Count {
             songs: value(&mut frame, "songs")?,
             playtime: value(&mut frame, "playtime")?,
         })
     }
+}
 
+impl Count {
     pub(crate) fn from_frame_grouped(
         frame: Frame,
         group_by: &Tag,
@@ -0,0 +1,95 @@
+use mpd_protocol::response::Frame;
+
+use crate::responses::{FromFrame, TypedResponseError};
+
+/// A decoder plugin, as returned by [`decoders`].
+///
+/// [`decoders`]: crate::commands::definitions::Decoders
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Decoder {
+    /// Name of the plugin.
+    pub plugin: String,
+    /// File name suffixes the plugin can decode.
+    pub suffixes: Vec<String>,
+    /// MIME types the plugin can decode.
+    pub mime_types: Vec<String>,
+}
+
+impl FromFrame for Vec<Decoder> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        parse_decoders(frame)
+    }
+}
+
+fn parse_decoders<I, K>(fields: I) -> Result<Vec<Decoder>, TypedResponseError>
+where
+    I: IntoIterator<Item = (K, String)>,
+    K: AsRef<str>,
+{
+    let mut out: Vec<Decoder> = Vec::new();
+
+    for (key, value) in fields {
+        match key.as_ref() {
+            "plugin" => out.push(Decoder {
+                plugin: value,
+                ..Default::default()
+            }),
+            "suffix" | "mime_type" => {
+                let Some(decoder) = out.last_mut() else {
+                    return Err(TypedResponseError::unexpected_field("plugin", key.as_ref()));
+                };
+
+                if key.as_ref() == "suffix" {
+                    decoder.suffixes.push(value);
+                } else {
+                    decoder.mime_types.push(value);
+                }
+            }
+            other => return Err(TypedResponseError::unexpected_field("plugin", other)),
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn decoders_parsing() {
+        let decoders = parse_decoders(vec![
+            ("plugin", String::from("mad")),
+            ("suffix", String::from("mp3")),
+            ("suffix", String::from("mp2")),
+            ("mime_type", String::from("audio/mpeg")),
+            ("plugin", String::from("hybrid_dsd")),
+            ("suffix", String::from("m4a")),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            decoders,
+            [
+                Decoder {
+                    plugin: String::from("mad"),
+                    suffixes: vec![String::from("mp3"), String::from("mp2")],
+                    mime_types: vec![String::from("audio/mpeg")],
+                },
+                Decoder {
+                    plugin: String::from("hybrid_dsd"),
+                    suffixes: vec![String::from("m4a")],
+                    mime_types: vec![],
+                },
+            ]
+        );
+
+        assert_matches!(
+            parse_decoders(vec![("suffix", String::from("mp3"))]),
+            Err(_)
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use mpd_protocol::response::Frame;
+
+use crate::responses::{song::SongBuilder, FromFieldValue, Song, Timestamp, TypedResponseError};
+
+/// An entry in a directory listing, as returned by the [`lsinfo`] and [`listfiles`] commands.
+///
+/// [`lsinfo`]: crate::commands::definitions::LsInfo
+/// [`listfiles`]: crate::commands::definitions::ListFiles
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DirectoryEntry {
+    /// A song in the database (only returned by `lsinfo`).
+    Song(Song),
+    /// A file, which may or may not be a song in the database (only returned by `listfiles`).
+    File {
+        /// Name of the file.
+        name: String,
+        /// Size of the file in bytes, if known.
+        size: Option<u64>,
+        /// Last modification date of the file, if known.
+        last_modified: Option<Timestamp>,
+    },
+    /// A subdirectory.
+    Directory {
+        /// Path of the directory.
+        path: String,
+        /// Last modification date of the directory, if known.
+        last_modified: Option<Timestamp>,
+    },
+    /// A stored playlist (only returned by `lsinfo`).
+    Playlist {
+        /// Name of the playlist.
+        name: String,
+        /// Last modification date of the playlist, if known.
+        last_modified: Option<Timestamp>,
+    },
+}
+
+impl DirectoryEntry {
+    pub(crate) fn from_frame_multi(
+        frame: Frame,
+        files_are_songs: bool,
+    ) -> Result<Vec<DirectoryEntry>, TypedResponseError> {
+        parse_entries(frame, files_are_songs)
+    }
+
+    /// Handle a field following the entry. Unknown fields are ignored, since newer servers may
+    /// add more information to the listing.
+    fn field(&mut self, key: &str, value: String) -> Result<(), TypedResponseError> {
+        match (self, key) {
+            (
+                DirectoryEntry::File { last_modified, .. }
+                | DirectoryEntry::Directory { last_modified, .. }
+                | DirectoryEntry::Playlist { last_modified, .. },
+                "Last-Modified",
+            ) => *last_modified = Some(Timestamp::from_value(value, key)?),
+            (DirectoryEntry::File { size, .. }, "size") => {
+                *size = Some(u64::from_value(value, key)?);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+enum PendingEntry {
+    Song(SongBuilder),
+    Other(DirectoryEntry),
+}
+
+impl PendingEntry {
+    fn finish(self) -> Option<DirectoryEntry> {
+        match self {
+            PendingEntry::Song(builder) => builder.finish().map(|s| DirectoryEntry::Song(s.song)),
+            PendingEntry::Other(entry) => Some(entry),
+        }
+    }
+}
+
+fn parse_entries<I, K>(
+    fields: I,
+    files_are_songs: bool,
+) -> Result<Vec<DirectoryEntry>, TypedResponseError>
+where
+    I: IntoIterator<Item = (K, String)>,
+    K: AsRef<str>,
+{
+    let mut out = Vec::new();
+    let mut current: Option<PendingEntry> = None;
+
+    for (key, value) in fields {
+        let key = key.as_ref();
+
+        let next = match key {
+            "file" if files_are_songs => {
+                let mut builder = SongBuilder::default();
+                builder.field(key, value)?;
+                PendingEntry::Song(builder)
+            }
+            "file" => PendingEntry::Other(DirectoryEntry::File {
+                name: value,
+                size: None,
+                last_modified: None,
+            }),
+            "directory" => PendingEntry::Other(DirectoryEntry::Directory {
+                path: value,
+                last_modified: None,
+            }),
+            "playlist" => PendingEntry::Other(DirectoryEntry::Playlist {
+                name: value,
+                last_modified: None,
+            }),
+            _ => {
+                match &mut current {
+                    Some(PendingEntry::Song(builder)) => {
+                        builder.field(key, value)?;
+                    }
+                    Some(PendingEntry::Other(entry)) => entry.field(key, value)?,
+                    None => return Err(TypedResponseError::unexpected_field("file", key)),
+                }
+
+                continue;
+            }
+        };
+
+        if let Some(entry) = current.replace(next).and_then(PendingEntry::finish) {
+            out.push(entry);
+        }
+    }
+
+    if let Some(entry) = current.and_then(PendingEntry::finish) {
+        out.push(entry);
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    const TEST_TIMESTAMP: &str = "2020-06-12T17:53:00Z";
+
+    #[test]
+    fn lsinfo_parsing() {
+        let entries = parse_entries(
+            vec![
+                ("directory", String::from("foo")),
+                ("Last-Modified", String::from(TEST_TIMESTAMP)),
+                ("file", String::from("bar.flac")),
+                ("Title", String::from("Bar")),
+                ("Last-Modified", String::from(TEST_TIMESTAMP)),
+                ("playlist", String::from("baz.m3u")),
+            ],
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_matches!(
+            &entries[0],
+            DirectoryEntry::Directory { path, last_modified: Some(_) } if path == "foo"
+        );
+        assert_matches!(
+            &entries[1],
+            DirectoryEntry::Song(song) if song.url == "bar.flac" && song.title() == Some("Bar")
+        );
+        assert_matches!(
+            &entries[2],
+            DirectoryEntry::Playlist { name, last_modified: None } if name == "baz.m3u"
+        );
+    }
+
+    #[test]
+    fn listfiles_parsing() {
+        let entries = parse_entries(
+            vec![
+                ("file", String::from("cover.jpg")),
+                ("size", String::from("1234")),
+                ("Last-Modified", String::from(TEST_TIMESTAMP)),
+                ("directory", String::from("foo")),
+            ],
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_matches!(
+            &entries[0],
+            DirectoryEntry::File { name, size: Some(1234), last_modified: Some(_) } if name == "cover.jpg"
+        );
+
+        assert_matches!(
+            parse_entries(vec![("size", String::from("1"))], false),
+            Err(_)
+        );
+    }
+
+    #[test]
+    fn unknown_fields_ignored() {
+        let entries = parse_entries(
+            vec![
+                ("directory", String::from("foo")),
+                ("Added", String::from(TEST_TIMESTAMP)),
+                ("Last-Modified", String::from(TEST_TIMESTAMP)),
+                ("playlist", String::from("baz.m3u")),
+                ("size", String::from("1234")),
+            ],
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_matches!(
+            &entries[0],
+            DirectoryEntry::Directory { path, last_modified: Some(_) } if path == "foo"
+        );
+        assert_matches!(
+            &entries[1],
+            DirectoryEntry::Playlist { name, last_modified: None } if name == "baz.m3u"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::responses::Count;
+
+/// An album in the database, as returned by [`Client::albums`].
+///
+/// [`Client::albums`]: crate::Client::albums
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Album {
+    /// Name of the album (the `Album` tag).
+    pub name: String,
+    /// Artist of the album (the `AlbumArtist` tag), if any.
+    pub artist: Option<String>,
+    /// Sort name of the album (the `AlbumSort` tag), if any.
+    pub sort_name: Option<String>,
+    /// MusicBrainz release ID of the album, if any.
+    pub musicbrainz_id: Option<String>,
+    /// Number of songs on the album.
+    pub songs: u64,
+    /// Total playtime of the songs on the album.
+    pub playtime: Duration,
+}
+
+/// An artist in the database, as returned by [`Client::artists`].
+///
+/// [`Client::artists`]: crate::Client::artists
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Artist {
+    /// Name of the artist (the `Artist` tag).
+    pub name: String,
+    /// Sort name of the artist (the `ArtistSort` tag), if any.
+    pub sort_name: Option<String>,
+    /// MusicBrainz artist ID, if any.
+    pub musicbrainz_id: Option<String>,
+    /// Number of songs by the artist.
+    pub songs: u64,
+    /// Total playtime of the songs by the artist.
+    pub playtime: Duration,
+}
+
+/// A distinct album in a `list` response, identified by its name, album artist and MusicBrainz
+/// release ID.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Release<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) artist: &'a str,
+    pub(crate) musicbrainz_id: &'a str,
+    sort_name: Option<&'a str>,
+}
+
+impl<'a> Release<'a> {
+    fn key(&self) -> (&'a str, &'a str, &'a str) {
+        (self.name, self.artist, self.musicbrainz_id)
+    }
+
+    fn to_album(&self, count: Count) -> Album {
+        let non_empty = |value: &str| Some(value).filter(|v| !v.is_empty()).map(String::from);
+
+        Album {
+            name: self.name.to_owned(),
+            artist: non_empty(self.artist),
+            sort_name: self.sort_name.map(String::from),
+            musicbrainz_id: non_empty(self.musicbrainz_id),
+            songs: count.songs,
+            playtime: count.playtime,
+        }
+    }
+}
+
+impl Album {
+    /// Collect the distinct albums from a `list` response grouped by album artist, MusicBrainz
+    /// release ID and sort name, in the order they first appear.
+    ///
+    /// If different songs of an album have different sort names, the first non-empty one is
+    /// used.
+    pub(crate) fn releases<'a, I>(details: I) -> Vec<Release<'a>>
+    where
+        I: IntoIterator<Item = (&'a str, [&'a str; 3])>,
+    {
+        let mut releases: Vec<Release<'a>> = Vec::new();
+        let mut index = HashMap::new();
+
+        for (name, [artist, musicbrainz_id, sort_name]) in details {
+            let i = *index
+                .entry((name, artist, musicbrainz_id))
+                .or_insert_with(|| {
+                    releases.push(Release {
+                        name,
+                        artist,
+                        musicbrainz_id,
+                        sort_name: None,
+                    });
+                    releases.len() - 1
+                });
+
+            let release = &mut releases[i];
+            if release.sort_name.is_none() && !sort_name.is_empty() {
+                release.sort_name = Some(sort_name);
+            }
+        }
+
+        releases
+    }
+
+    /// Returns the albums which share their name with another album. Their songs can't be
+    /// counted by grouping by name, so they need to be counted separately.
+    pub(crate) fn ambiguous_releases<'r, 'a>(
+        releases: &'r [Release<'a>],
+    ) -> impl Iterator<Item = &'r Release<'a>> {
+        let mut names: HashMap<&str, usize> = HashMap::new();
+
+        for release in releases {
+            *names.entry(release.name).or_default() += 1;
+        }
+
+        releases.iter().filter(move |r| names[r.name] > 1)
+    }
+
+    /// Combine the counts grouped by album name with the distinct albums, using the separate
+    /// counts (in the order of [`Album::ambiguous_releases`]) for albums sharing their name.
+    pub(crate) fn from_responses(
+        counts: Vec<(String, Count)>,
+        releases: &[Release<'_>],
+        separate_counts: Vec<Count>,
+    ) -> Vec<Album> {
+        let separate_counts: HashMap<_, _> = Album::ambiguous_releases(releases)
+            .map(Release::key)
+            .zip(separate_counts)
+            .collect();
+
+        let mut by_name: HashMap<&str, Vec<&Release<'_>>> = HashMap::new();
+        for release in releases {
+            by_name.entry(release.name).or_default().push(release);
+        }
+
+        let mut out = Vec::with_capacity(releases.len());
+
+        for (name, count) in counts {
+            match by_name.get(name.as_str()).map(Vec::as_slice) {
+                Some([release]) => out.push(release.to_album(count)),
+                Some(releases) => {
+                    out.extend(releases.iter().map(|release| {
+                        let count = separate_counts.get(&release.key()).copied();
+                        release.to_album(count.unwrap_or(count_zero()))
+                    }));
+                }
+                None => out.push(Album {
+                    name,
+                    artist: None,
+                    sort_name: None,
+                    musicbrainz_id: None,
+                    songs: count.songs,
+                    playtime: count.playtime,
+                }),
+            }
+        }
+
+        out
+    }
+}
+
+fn count_zero() -> Count {
+    Count {
+        songs: 0,
+        playtime: Duration::ZERO,
+    }
+}
+
+impl Artist {
+    pub(crate) fn from_responses<'a, I>(counts: Vec<(String, Count)>, details: I) -> Vec<Artist>
+    where
+        I: IntoIterator<Item = (&'a str, [&'a str; 2])>,
+    {
+        merge(counts, details)
+            .map(|(name, sort_name, musicbrainz_id, count)| Artist {
+                name,
+                sort_name,
+                musicbrainz_id,
+                songs: count.songs,
+                playtime: count.playtime,
+            })
+            .collect()
+    }
+}
+
+type Merged = (String, Option<String>, Option<String>, Count);
+
+/// Combine the grouped counts with the sort names and IDs from a `list` response grouped by both.
+///
+/// If different songs have different sort names or IDs for the same name, the first non-empty
+/// value is used.
+fn merge<'a, I>(counts: Vec<(String, Count)>, details: I) -> impl Iterator<Item = Merged> + 'a
+where
+    I: IntoIterator<Item = (&'a str, [&'a str; 2])>,
+{
+    let mut found: HashMap<&'a str, [Option<&'a str>; 2]> = HashMap::new();
+
+    for (name, values) in details {
+        let entry = found.entry(name).or_default();
+
+        for (existing, value) in entry.iter_mut().zip(values) {
+            if existing.is_none() && !value.is_empty() {
+                *existing = Some(value);
+            }
+        }
+    }
+
+    counts.into_iter().map(move |(name, count)| {
+        let [sort_name, id] = found.get(name.as_str()).copied().unwrap_or_default();
+        (
+            name,
+            sort_name.map(String::from),
+            id.map(String::from),
+            count,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(songs: u64) -> Count {
+        Count {
+            songs,
+            playtime: Duration::from_secs(songs * 100),
+        }
+    }
+
+    #[test]
+    fn merge_artist_details() {
+        let counts = vec![
+            (String::from("Foo"), count(2)),
+            (String::from("Bar"), count(1)),
+        ];
+        let details = vec![("Foo", ["", "1234"]), ("Foo", ["Foo, The", "5678"])];
+
+        let artists = Artist::from_responses(counts, details);
+
+        assert_eq!(
+            artists,
+            [
+                Artist {
+                    name: String::from("Foo"),
+                    sort_name: Some(String::from("Foo, The")),
+                    musicbrainz_id: Some(String::from("1234")),
+                    songs: 2,
+                    playtime: Duration::from_secs(200),
+                },
+                Artist {
+                    name: String::from("Bar"),
+                    sort_name: None,
+                    musicbrainz_id: None,
+                    songs: 1,
+                    playtime: Duration::from_secs(100),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn albums_with_same_name() {
+        let counts = vec![
+            (String::from("Foo"), count(5)),
+            (String::from("Bar"), count(1)),
+        ];
+        let details = vec![
+            ("Bar", ["Someone", "", ""]),
+            ("Foo", ["A", "1234", ""]),
+            ("Foo", ["A", "1234", "Foo, The"]),
+            ("Foo", ["B", "5678", ""]),
+        ];
+
+        let releases = Album::releases(details);
+        assert_eq!(releases.len(), 3);
+
+        let ambiguous: Vec<_> = Album::ambiguous_releases(&releases)
+            .map(|r| r.musicbrainz_id)
+            .collect();
+        assert_eq!(ambiguous, ["1234", "5678"]);
+
+        let albums = Album::from_responses(counts, &releases, vec![count(3), count(2)]);
+
+        assert_eq!(
+            albums,
+            [
+                Album {
+                    name: String::from("Foo"),
+                    artist: Some(String::from("A")),
+                    sort_name: Some(String::from("Foo, The")),
+                    musicbrainz_id: Some(String::from("1234")),
+                    songs: 3,
+                    playtime: Duration::from_secs(300),
+                },
+                Album {
+                    name: String::from("Foo"),
+                    artist: Some(String::from("B")),
+                    sort_name: None,
+                    musicbrainz_id: Some(String::from("5678")),
+                    songs: 2,
+                    playtime: Duration::from_secs(200),
+                },
+                Album {
+                    name: String::from("Bar"),
+                    artist: Some(String::from("Someone")),
+                    sort_name: None,
+                    musicbrainz_id: None,
+                    songs: 1,
+                    playtime: Duration::from_secs(100),
+                },
+            ]
+        );
+    }
+}
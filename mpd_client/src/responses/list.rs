@@ -50,6 +50,24 @@ impl<const N: usize> List<N> {
         }
     }
 
+    /// Returns the values of the primary tag, nested under the combination of grouped values they
+    /// were returned for.
+    ///
+    /// For example, when listing albums grouped by album artist, this returns each album artist
+    /// with the list of their albums. Groups appear in the order they were returned by the server.
+    pub fn groups(&self) -> Vec<([&str; N], Vec<&str>)> {
+        let mut out: Vec<([&str; N], Vec<&str>)> = Vec::new();
+
+        for (value, group) in self.grouped_values() {
+            match out.last_mut() {
+                Some((last, values)) if *last == group => values.push(value),
+                _ => out.push((group, vec![value])),
+            }
+        }
+
+        out
+    }
+
     /// Returns the tags the response was grouped by.
     pub fn grouped_by(&self) -> &[Tag; N] {
         &self.groupings
@@ -226,4 +244,24 @@ mod tests {
         assert_eq!(iter.next(), Some(("Title 4", ["Qwert", "Asdf"])));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn nested_groups() {
+        let list = List {
+            primary_tag: Tag::Album,
+            groupings: [Tag::AlbumArtist],
+            fields: vec![
+                (Tag::AlbumArtist, String::from("Foo")),
+                (Tag::Album, String::from("Bar")),
+                (Tag::Album, String::from("Quz")),
+                (Tag::AlbumArtist, String::from("Asdf")),
+                (Tag::Album, String::from("Qwert")),
+            ],
+        };
+
+        assert_eq!(
+            list.groups(),
+            [(["Foo"], vec!["Bar", "Quz"]), (["Asdf"], vec!["Qwert"])]
+        );
+    }
 }
@@ -1,26 +1,71 @@
 //! Typed responses to individual commands.
 
+mod audio_format;
+mod comments;
 mod count;
+mod decoder;
+mod directory;
+mod library;
 mod list;
+mod neighbor;
+mod output;
 mod playlist;
 mod song;
 mod sticker;
 mod timestamp;
+mod update_job;
 
-use std::{error::Error, fmt, num::ParseIntError, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    num::ParseIntError,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use bytes::BytesMut;
+/// Derive macro generating an implementation of [`FromFrame`] for structs.
+///
+/// ```
+/// use mpd_client::responses::FromFrame;
+///
+/// #[derive(FromFrame)]
+/// struct PluginStatus {
+///     /// Read from the `count` field, which must be present.
+///     count: u32,
+///     /// Read from the `name` field, if present.
+///     name: Option<String>,
+///     /// Read from the `plugin-state` field, or `false` if missing.
+///     #[mpd(rename = "plugin-state", default)]
+///     enabled: bool,
+/// }
+/// ```
+///
+/// Fields are converted using [`FromFieldValue`], and are required unless their type is an
+/// `Option` or they have the `default` attribute.
+#[cfg(feature = "derive")]
+pub use mpd_client_derive::FromFrame;
 use mpd_protocol::response::Frame;
 
 pub use self::{
+    audio_format::{AudioFormat, SampleFormat},
+    comments::Comments,
     count::Count,
+    decoder::Decoder,
+    directory::DirectoryEntry,
+    library::{Album, Artist},
     list::{GroupedListValuesIter, List, ListValuesIntoIter, ListValuesIter},
+    neighbor::Neighbor,
+    output::Output,
     playlist::Playlist,
-    song::{Song, SongInQueue, SongRange},
-    sticker::{StickerFind, StickerGet, StickerList},
+    song::{Song, SongInQueue, SongRange, TagNormalization},
+    sticker::{Sticker, StickerFind, StickerGet, StickerList},
     timestamp::Timestamp,
+    update_job::UpdateJob,
 };
-use crate::commands::{ReplayGainMode, SingleMode, SongId, SongPosition};
+use crate::commands::{ConsumeMode, ReplayGainMode, SingleMode, SongId, SongPosition};
 
 type KeyValuePair = (Arc<str>, String);
 
@@ -124,9 +169,161 @@ impl Error for TypedResponseError {
     }
 }
 
+/// Typed responses which can be converted from a raw [`Frame`] on their own.
+///
+/// This is implemented for the responses of commands that don't need any of their arguments to
+/// interpret the response. Combined with [`FromFrames`], this allows converting the frames of a
+/// [raw command list][crate::Client::raw_command_list] back into typed responses.
+pub trait FromFrame: Sized {
+    /// Convert the raw frame into the typed response.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the frame is not a valid response of this type.
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError>;
+}
+
+impl FromFrame for () {
+    fn from_frame(_: Frame) -> Result<Self, TypedResponseError> {
+        Ok(())
+    }
+}
+
+impl FromFrame for Vec<SongInQueue> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        SongInQueue::from_frame_multi(frame)
+    }
+}
+
+impl FromFrame for Option<SongInQueue> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        SongInQueue::from_frame_single(frame)
+    }
+}
+
+impl FromFrame for Vec<Song> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Song::from_frame_multi(frame)
+    }
+}
+
+impl FromFrame for Vec<Playlist> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Playlist::parse_frame(frame)
+    }
+}
+
+impl FromFrame for Option<AlbumArt> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        // The offset of the chunk is unknown here, so the data may not be the start of the file
+        AlbumArt::from_frame(frame, false)
+    }
+}
+
+/// Typed responses which can be converted from a raw [`Frame`] leniently, i.e. skipping invalid
+/// fields instead of failing the entire conversion.
+///
+/// See [`Lenient`][crate::commands::Lenient] for using this with commands.
+pub trait FromFrameLenient: Sized {
+    /// Convert the raw frame into the typed response, skipping fields that could not be
+    /// converted.
+    ///
+    /// The errors for the skipped fields are returned alongside the response.
+    fn from_frame_lenient(frame: Frame) -> (Self, Vec<TypedResponseError>);
+}
+
+impl FromFrameLenient for Vec<SongInQueue> {
+    fn from_frame_lenient(frame: Frame) -> (Self, Vec<TypedResponseError>) {
+        SongInQueue::from_fields_lenient(frame)
+    }
+}
+
+impl FromFrameLenient for Vec<Song> {
+    fn from_frame_lenient(frame: Frame) -> (Self, Vec<TypedResponseError>) {
+        let (songs, errors) = SongInQueue::from_fields_lenient(frame);
+        (songs.into_iter().map(|s| s.song).collect(), errors)
+    }
+}
+
+/// Typed responses which can be converted from the raw frames of a command list.
+///
+/// This is implemented for tuples of [`FromFrame`] types, where each frame is converted using the
+/// type in the same position, and for a [`Vec`] of the same [`FromFrame`] type.
+///
+/// ```
+/// use mpd_client::{
+///     protocol::response::Frame,
+///     responses::{FromFrames, Stats, Status},
+/// };
+///
+/// fn convert(frames: Vec<Frame>) -> (Status, Stats) {
+///     <(Status, Stats)>::from_frames(frames).expect("invalid response")
+/// }
+/// ```
+pub trait FromFrames: Sized {
+    /// Convert the raw frames into the typed responses.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the number of frames does not match, or if any of the frames fail
+    /// to convert.
+    fn from_frames(frames: Vec<Frame>) -> Result<Self, TypedResponseError>;
+}
+
+impl<T> FromFrames for Vec<T>
+where
+    T: FromFrame,
+{
+    fn from_frames(frames: Vec<Frame>) -> Result<Self, TypedResponseError> {
+        frames.into_iter().map(T::from_frame).collect()
+    }
+}
+
+macro_rules! impl_from_frames_tuple {
+    ($($type:ident),+) => {
+        impl<$($type),+> FromFrames for ($($type,)+)
+        where
+            $(
+                $type: FromFrame
+            ),+
+        {
+            fn from_frames(frames: Vec<Frame>) -> Result<Self, TypedResponseError> {
+                let mut frames = frames.into_iter();
+
+                let responses = ($(
+                    $type::from_frame(frames.next().ok_or_else(TypedResponseError::other)?)?,
+                )+);
+
+                if frames.next().is_some() {
+                    return Err(TypedResponseError::other());
+                }
+
+                Ok(responses)
+            }
+        }
+    };
+}
+
+impl_from_frames_tuple!(A);
+impl_from_frames_tuple!(A, B);
+impl_from_frames_tuple!(A, B, C);
+impl_from_frames_tuple!(A, B, C, D);
+impl_from_frames_tuple!(A, B, C, D, E);
+impl_from_frames_tuple!(A, B, C, D, E, F);
+impl_from_frames_tuple!(A, B, C, D, E, F, G);
+impl_from_frames_tuple!(A, B, C, D, E, F, G, H);
+
 /// Types which can be converted from a field value.
-pub(crate) trait FromFieldValue: Sized {
+///
+/// This is used by [`value`] and [`optional_value`], and can be implemented for your own types
+/// when implementing custom [`Command`][crate::commands::Command]s.
+pub trait FromFieldValue: Sized {
     /// Convert the value.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the value is not valid for this type. `field` is the name of the
+    /// field the value came from, for use in the error.
     fn from_value(v: String, field: &str) -> Result<Self, TypedResponseError>;
 }
 
@@ -140,20 +337,29 @@ impl FromFieldValue for bool {
     }
 }
 
+impl FromFieldValue for String {
+    fn from_value(v: String, _: &str) -> Result<Self, TypedResponseError> {
+        Ok(v)
+    }
+}
+
 impl FromFieldValue for Duration {
     fn from_value(v: String, field: &str) -> Result<Self, TypedResponseError> {
         parse_duration(field, v)
     }
 }
 
+impl FromFieldValue for f32 {
+    fn from_value(v: String, field: &str) -> Result<Self, TypedResponseError> {
+        v.parse::<f32>()
+            .map_err(|e| TypedResponseError::invalid_value(field, v).source(e))
+    }
+}
+
 impl FromFieldValue for PlayState {
     fn from_value(v: String, field: &str) -> Result<Self, TypedResponseError> {
-        match &*v {
-            "play" => Ok(PlayState::Playing),
-            "pause" => Ok(PlayState::Paused),
-            "stop" => Ok(PlayState::Stopped),
-            _ => Err(TypedResponseError::invalid_value(field, v)),
-        }
+        v.parse()
+            .map_err(|e| TypedResponseError::invalid_value(field, v).source(e))
     }
 }
 
@@ -202,7 +408,36 @@ impl FromFieldValue for usize {
 }
 
 /// Get a *required* value for the given field, as the given type.
-pub(crate) fn value<V: FromFieldValue>(
+///
+/// The field is removed from the frame. This is intended for implementing custom
+/// [`Command`][crate::commands::Command]s:
+///
+/// ```
+/// use mpd_client::{
+///     commands::Command,
+///     protocol::{command::Command as RawCommand, response::Frame},
+///     responses::{optional_value, value, TypedResponseError},
+/// };
+///
+/// struct PluginStatus;
+///
+/// impl Command for PluginStatus {
+///     type Response = (u32, Option<String>);
+///
+///     fn command(&self) -> RawCommand {
+///         RawCommand::new("pluginstatus")
+///     }
+///
+///     fn response(self, mut frame: Frame) -> Result<Self::Response, TypedResponseError> {
+///         Ok((value(&mut frame, "count")?, optional_value(&mut frame, "name")?))
+///     }
+/// }
+/// ```
+///
+/// # Errors
+///
+/// This returns an error if the field is missing or its value fails to convert.
+pub fn value<V: FromFieldValue>(
     frame: &mut Frame,
     field: &'static str,
 ) -> Result<V, TypedResponseError> {
@@ -213,7 +448,13 @@ pub(crate) fn value<V: FromFieldValue>(
 }
 
 /// Get an *optional* value for the given field, as the given type.
-fn optional_value<V: FromFieldValue>(
+///
+/// The field is removed from the frame. See [`value`] for an example.
+///
+/// # Errors
+///
+/// This returns an error if the field is present but its value fails to convert.
+pub fn optional_value<V: FromFieldValue>(
     frame: &mut Frame,
     field: &'static str,
 ) -> Result<Option<V>, TypedResponseError> {
@@ -226,19 +467,51 @@ fn optional_value<V: FromFieldValue>(
     }
 }
 
+/// Fields of a response, keyed by name. Fields are removed as they are converted, leaving the
+/// unrecognized ones.
+type FieldMap = HashMap<Arc<str>, String>;
+
+fn take_value<V: FromFieldValue>(
+    fields: &mut FieldMap,
+    field: &'static str,
+) -> Result<V, TypedResponseError> {
+    let value = fields
+        .remove(field)
+        .ok_or_else(|| TypedResponseError::missing(field))?;
+    V::from_value(value, field)
+}
+
+fn take_optional_value<V: FromFieldValue>(
+    fields: &mut FieldMap,
+    field: &'static str,
+) -> Result<Option<V>, TypedResponseError> {
+    fields
+        .remove(field)
+        .map(|v| V::from_value(v, field))
+        .transpose()
+}
+
+/// Collect the fields which have not been removed from the frame yet.
+fn remaining_fields(frame: &Frame) -> HashMap<Arc<str>, String> {
+    frame
+        .fields()
+        .map(|(key, value)| (Arc::from(key), value.to_owned()))
+        .collect()
+}
+
 fn song_identifier(
-    frame: &mut Frame,
+    fields: &mut FieldMap,
     position_field: &'static str,
     id_field: &'static str,
 ) -> Result<Option<(SongPosition, SongId)>, TypedResponseError> {
     // The position field may or may not exist
-    let position = match optional_value(frame, position_field)? {
+    let position = match take_optional_value(fields, position_field)? {
         Some(p) => SongPosition(p),
         None => return Ok(None),
     };
 
     // ... but if the position field existed, the ID field must exist too
-    let id = value(frame, id_field).map(SongId)?;
+    let id = take_value(fields, id_field).map(SongId)?;
 
     Ok(Some((position, id)))
 }
@@ -269,6 +542,67 @@ pub enum PlayState {
     Paused,
 }
 
+impl PlayState {
+    fn as_str(self) -> &'static str {
+        match self {
+            PlayState::Stopped => "stop",
+            PlayState::Playing => "play",
+            PlayState::Paused => "pause",
+        }
+    }
+}
+
+/// Parses the protocol representation of the state (`play`, `pause` or `stop`).
+impl FromStr for PlayState {
+    type Err = ParseEnumError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw {
+            "play" => Ok(PlayState::Playing),
+            "pause" => Ok(PlayState::Paused),
+            "stop" => Ok(PlayState::Stopped),
+            _ => Err(ParseEnumError::new("play state", raw)),
+        }
+    }
+}
+
+/// Renders the protocol representation of the state.
+impl fmt::Display for PlayState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing one of the protocol enums (like [`PlayState`]) from an
+/// unrecognized string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseEnumError {
+    kind: &'static str,
+    value: String,
+}
+
+impl ParseEnumError {
+    pub(crate) fn new(kind: &'static str, value: &str) -> ParseEnumError {
+        ParseEnumError {
+            kind,
+            value: value.to_owned(),
+        }
+    }
+
+    /// Returns the value that failed to parse.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} {:?}", self.kind, self.value)
+    }
+}
+
+impl Error for ParseEnumError {}
+
 /// Response to the [`replay_gain_status`] command.
 ///
 /// See the [MPD documentation][replay-gain-status-command] for the specific meanings of the fields.
@@ -282,8 +616,8 @@ pub struct ReplayGainStatus {
     pub mode: ReplayGainMode,
 }
 
-impl ReplayGainStatus {
-    pub(crate) fn from_frame(mut raw: Frame) -> Result<Self, TypedResponseError> {
+impl FromFrame for ReplayGainStatus {
+    fn from_frame(mut raw: Frame) -> Result<Self, TypedResponseError> {
         let f = &mut raw;
         Ok(Self {
             mode: value(f, "replay_gain_mode")?,
@@ -297,15 +631,16 @@ impl ReplayGainStatus {
 ///
 /// [`status`]: crate::commands::definitions::Status
 /// [status-command]: https://www.musicpd.org/doc/html/protocol.html#command-status
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 #[allow(missing_docs)]
 #[non_exhaustive]
 pub struct Status {
-    pub volume: u8,
+    /// Current volume, or `None` if there is no mixer.
+    pub volume: Option<u8>,
     pub state: PlayState,
     pub repeat: bool,
     pub random: bool,
-    pub consume: bool,
+    pub consume: ConsumeMode,
     pub single: SingleMode,
     pub playlist_version: u32,
     pub playlist_length: usize,
@@ -314,58 +649,248 @@ pub struct Status {
     pub elapsed: Option<Duration>,
     pub duration: Option<Duration>,
     pub bitrate: Option<u64>,
+    /// Format of the audio currently being decoded.
+    pub audio_format: Option<AudioFormat>,
     pub crossfade: Duration,
+    /// MixRamp threshold in decibels.
+    pub mixramp_db: Option<f32>,
+    /// MixRamp delay, if enabled.
+    pub mixramp_delay: Option<Duration>,
     pub update_job: Option<u64>,
     pub error: Option<String>,
     pub partition: Option<String>,
+    /// Fields returned by the server that are not otherwise recognized.
+    pub extra: HashMap<Arc<str>, String>,
+    /// Time at which the response was parsed, which `elapsed` refers to.
+    ///
+    /// This is taken from the Tokio clock, so it follows paused or advanced time in tests.
+    ///
+    /// This is not considered when comparing two `Status` values.
+    pub received_at: Instant,
+}
+
+impl Status {
+    /// Returns the estimated playback position at the given time.
+    ///
+    /// While playing, this extrapolates from `elapsed` based on the time passed since
+    /// `received_at`, clamped to the `duration` of the song if known. Otherwise, this returns
+    /// `elapsed` as-is.
+    ///
+    /// This allows animating a progress indicator without polling the status repeatedly.
+    pub fn elapsed_at(&self, now: Instant) -> Option<Duration> {
+        let elapsed = self.elapsed?;
+
+        if self.state != PlayState::Playing {
+            return Some(elapsed);
+        }
+
+        let estimated = elapsed + now.saturating_duration_since(self.received_at);
+
+        match self.duration {
+            Some(duration) => Some(estimated.min(duration)),
+            None => Some(estimated),
+        }
+    }
+}
+
+impl Status {
+    /// Determine which parts of the status changed between `old` and `new`.
+    ///
+    /// This is useful for only updating the affected parts of a user interface after receiving a
+    /// state change notification.
+    pub fn diff(old: &Status, new: &Status) -> StatusDelta {
+        StatusDelta {
+            state: old.state != new.state,
+            song: old.current_song != new.current_song,
+            next_song: old.next_song != new.next_song,
+            position: old.elapsed != new.elapsed || old.duration != new.duration,
+            audio: old.bitrate != new.bitrate || old.audio_format != new.audio_format,
+            volume: old.volume != new.volume,
+            options: old.repeat != new.repeat
+                || old.random != new.random
+                || old.consume != new.consume
+                || old.single != new.single
+                || old.crossfade != new.crossfade
+                || old.mixramp_db != new.mixramp_db
+                || old.mixramp_delay != new.mixramp_delay,
+            queue: old.playlist_version != new.playlist_version
+                || old.playlist_length != new.playlist_length,
+            update: old.update_job != new.update_job,
+            error: old.error != new.error,
+            partition: old.partition != new.partition,
+        }
+    }
+}
+
+/// The parts of a [`Status`] that changed, as returned by [`Status::diff`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StatusDelta {
+    /// The playback state (playing, paused, stopped).
+    pub state: bool,
+    /// The current song.
+    pub song: bool,
+    /// The next song.
+    pub next_song: bool,
+    /// The elapsed time or duration of the current song.
+    pub position: bool,
+    /// The bitrate or audio format.
+    pub audio: bool,
+    /// The volume.
+    pub volume: bool,
+    /// The playback options (repeat, random, consume, single, crossfade and MixRamp).
+    pub options: bool,
+    /// The version or length of the queue.
+    pub queue: bool,
+    /// The database update job.
+    pub update: bool,
+    /// The error message.
+    pub error: bool,
+    /// The partition.
+    pub partition: bool,
+}
+
+impl StatusDelta {
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        *self == StatusDelta::default()
+    }
+}
+
+impl PartialEq for Status {
+    fn eq(&self, other: &Self) -> bool {
+        let Status {
+            volume,
+            state,
+            repeat,
+            random,
+            consume,
+            single,
+            playlist_version,
+            playlist_length,
+            current_song,
+            next_song,
+            elapsed,
+            duration,
+            bitrate,
+            audio_format,
+            crossfade,
+            mixramp_db,
+            mixramp_delay,
+            update_job,
+            error,
+            partition,
+            extra,
+            received_at: _,
+        } = self;
+
+        *volume == other.volume
+            && *state == other.state
+            && *repeat == other.repeat
+            && *random == other.random
+            && *consume == other.consume
+            && *single == other.single
+            && *playlist_version == other.playlist_version
+            && *playlist_length == other.playlist_length
+            && *current_song == other.current_song
+            && *next_song == other.next_song
+            && *elapsed == other.elapsed
+            && *duration == other.duration
+            && *bitrate == other.bitrate
+            && *audio_format == other.audio_format
+            && *crossfade == other.crossfade
+            && *mixramp_db == other.mixramp_db
+            && *mixramp_delay == other.mixramp_delay
+            && *update_job == other.update_job
+            && *error == other.error
+            && *partition == other.partition
+            && *extra == other.extra
+    }
+}
+
+impl FromFrame for Status {
+    fn from_frame(raw: Frame) -> Result<Self, TypedResponseError> {
+        Status::from_fields(raw)
+    }
 }
 
 impl Status {
-    pub(crate) fn from_frame(mut raw: Frame) -> Result<Self, TypedResponseError> {
-        let single = match raw.get("single") {
+    fn from_fields<I, K>(fields: I) -> Result<Self, TypedResponseError>
+    where
+        I: IntoIterator<Item = (K, String)>,
+        K: Into<Arc<str>>,
+    {
+        let mut f: FieldMap = fields.into_iter().map(|(k, v)| (k.into(), v)).collect();
+        let f = &mut f;
+
+        let single = match f.remove("single") {
             None => SingleMode::Disabled,
-            Some(val) => match val.as_str() {
-                "0" => SingleMode::Disabled,
-                "1" => SingleMode::Enabled,
-                "oneshot" => SingleMode::Oneshot,
-                _ => return Err(TypedResponseError::invalid_value("single", val)),
-            },
+            Some(val) => val
+                .parse()
+                .map_err(|e| TypedResponseError::invalid_value("single", val).source(e))?,
+        };
+
+        let consume = match f.remove("consume") {
+            None => return Err(TypedResponseError::missing("consume")),
+            Some(val) => val
+                .parse()
+                .map_err(|e| TypedResponseError::invalid_value("consume", val).source(e))?,
+        };
+
+        let volume = match f.remove("volume") {
+            // MPD reports a volume of -1 if there is no mixer
+            Some(val) if val == "-1" => None,
+            Some(val) => Some(u8::from_value(val, "volume")?),
+            None => None,
+        };
+
+        // The legacy `time` field contains the elapsed time and duration in whole seconds. It is
+        // only used if the precise `elapsed` and `duration` fields are missing (protocol versions
+        // <0.20). Older servers sent it with an uppercase name.
+        let time = match (f.remove("time"), f.remove("Time")) {
+            (Some(time), _) => Some((time, "time")),
+            (None, Some(time)) => Some((time, "Time")),
+            (None, None) => None,
         };
 
-        let duration = if let Some(val) = raw.get("duration") {
-            Some(Duration::from_value(val, "duration")?)
-        } else if let Some(time) = raw.get("Time") {
-            // Backwards compatibility with protocol versions <0.20
-            if let Some((_, duration)) = time.split_once(':') {
-                Some(Duration::from_value(duration.to_owned(), "Time")?)
-            } else {
+        let (time_elapsed, time_duration) = match time {
+            Some((time, field)) => match time.split_once(':') {
+                Some((elapsed, duration)) => (
+                    Some(Duration::from_value(elapsed.to_owned(), field)?),
+                    Some(Duration::from_value(duration.to_owned(), field)?),
+                ),
                 // No separator
-                return Err(TypedResponseError::invalid_value("Time", time));
-            }
-        } else {
-            None
+                None => return Err(TypedResponseError::invalid_value(field, time)),
+            },
+            None => (None, None),
         };
 
-        let f = &mut raw;
+        let elapsed = take_optional_value(f, "elapsed")?.or(time_elapsed);
+        let duration = take_optional_value(f, "duration")?.or(time_duration);
 
         Ok(Self {
-            volume: optional_value(f, "volume")?.unwrap_or(0),
-            state: value(f, "state")?,
-            repeat: value(f, "repeat")?,
-            random: value(f, "random")?,
-            consume: value(f, "consume")?,
+            volume,
+            state: take_value(f, "state")?,
+            repeat: take_value(f, "repeat")?,
+            random: take_value(f, "random")?,
+            consume,
             single,
-            playlist_length: optional_value(f, "playlistlength")?.unwrap_or(0),
-            playlist_version: optional_value(f, "playlist")?.unwrap_or(0),
+            playlist_length: take_optional_value(f, "playlistlength")?.unwrap_or(0),
+            playlist_version: take_optional_value(f, "playlist")?.unwrap_or(0),
             current_song: song_identifier(f, "song", "songid")?,
             next_song: song_identifier(f, "nextsong", "nextsongid")?,
-            elapsed: optional_value(f, "elapsed")?,
+            elapsed,
             duration,
-            bitrate: optional_value(f, "bitrate")?,
-            crossfade: optional_value(f, "xfade")?.unwrap_or(Duration::ZERO),
-            update_job: optional_value(f, "update_job")?,
-            error: f.get("error"),
-            partition: f.get("partition"),
+            bitrate: take_optional_value(f, "bitrate")?,
+            audio_format: take_optional_value(f, "audio")?,
+            crossfade: take_optional_value(f, "xfade")?.unwrap_or(Duration::ZERO),
+            mixramp_db: take_optional_value(f, "mixrampdb")?,
+            mixramp_delay: take_optional_value(f, "mixrampdelay")?,
+            update_job: take_optional_value(f, "updating_db")?,
+            error: f.remove("error"),
+            partition: f.remove("partition"),
+            extra: std::mem::take(f),
+            received_at: tokio::time::Instant::now().into_std(),
         })
     }
 }
@@ -373,7 +898,7 @@ impl Status {
 /// Response to the [`stats`] command, containing general server statistics.
 ///
 /// [`stats`]: crate::commands::definitions::Stats
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[allow(missing_docs)]
 #[non_exhaustive]
 pub struct Stats {
@@ -385,10 +910,29 @@ pub struct Stats {
     pub db_playtime: Duration,
     /// Raw server UNIX timestamp of last database update.
     pub db_last_update: u64,
+    /// Fields returned by the server that are not otherwise recognized.
+    pub extra: HashMap<Arc<str>, String>,
 }
 
 impl Stats {
-    pub(crate) fn from_frame(mut f: Frame) -> Result<Self, TypedResponseError> {
+    /// Returns the time of the last database update.
+    pub fn db_last_update_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.db_last_update)
+    }
+
+    /// Returns the time elapsed since the last database update, according to the local clock.
+    ///
+    /// This returns `None` if the update appears to be in the future, e.g. because the clocks of
+    /// the client and server differ.
+    pub fn time_since_db_update(&self) -> Option<Duration> {
+        SystemTime::now()
+            .duration_since(self.db_last_update_time())
+            .ok()
+    }
+}
+
+impl FromFrame for Stats {
+    fn from_frame(mut f: Frame) -> Result<Self, TypedResponseError> {
         let f = &mut f;
         Ok(Self {
             artists: value(f, "artists")?,
@@ -398,6 +942,32 @@ impl Stats {
             playtime: value(f, "playtime")?,
             db_playtime: value(f, "db_playtime")?,
             db_last_update: value(f, "db_update")?,
+            extra: remaining_fields(f),
+        })
+    }
+}
+
+/// Response to the [`config`] command, containing the server configuration.
+///
+/// [`config`]: crate::commands::definitions::Config
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Config {
+    /// Absolute path of the music directory.
+    pub music_directory: String,
+    /// Absolute path of the playlist directory, if reported by the server.
+    pub playlist_directory: Option<String>,
+    /// Whether the server supports regular expressions in filters.
+    pub pcre: bool,
+}
+
+impl FromFrame for Config {
+    fn from_frame(mut f: Frame) -> Result<Self, TypedResponseError> {
+        let f = &mut f;
+        Ok(Self {
+            music_directory: value(f, "music_directory")?,
+            playlist_directory: optional_value(f, "playlist_directory")?,
+            pcre: optional_value(f, "pcre")?.unwrap_or(false),
         })
     }
 }
@@ -410,25 +980,54 @@ pub struct AlbumArt {
     /// The total size in bytes of the file.
     pub size: usize,
     /// The mime type, if known.
+    ///
+    /// If the server did not report a type, this is detected from the data for the first chunk
+    /// (offset 0) of common image formats (JPEG, PNG, WebP, GIF).
     pub mime: Option<String>,
     /// The raw data.
     pub data: BytesMut,
 }
 
 impl AlbumArt {
-    pub(crate) fn from_frame(mut frame: Frame) -> Result<Option<Self>, TypedResponseError> {
+    /// Convert the frame, detecting the mime type if `sniff_mime` is set and the server didn't
+    /// report one.
+    pub(crate) fn from_frame(
+        mut frame: Frame,
+        sniff_mime: bool,
+    ) -> Result<Option<Self>, TypedResponseError> {
         let Some(data) = frame.take_binary() else {
             return Ok(None);
         };
 
+        let mime = match frame.get("type") {
+            Some(mime) => Some(mime),
+            None if sniff_mime => detect_image_mime(&data).map(String::from),
+            None => None,
+        };
+
         Ok(Some(AlbumArt {
             size: value(&mut frame, "size")?,
-            mime: frame.get("type"),
+            mime,
             data,
         }))
     }
 }
 
+/// Detect the mime type of image data based on its magic bytes.
+fn detect_image_mime(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\xFF\xD8\xFF") {
+        Some("image/jpeg")
+    } else if data.starts_with(b"\x89PNG\r\n\x1A\n") {
+        Some("image/png")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else {
+        None
+    }
+}
+
 /// Parse response for the [`crate::commands::ReadChannelMessages`] command.
 pub(crate) fn parse_channel_messages<F>(
     fields: F,
@@ -458,11 +1057,24 @@ where
     Ok(response)
 }
 
+/// Receive the successful frames of a raw response, for testing conversions of frames.
+#[cfg(test)]
+pub(crate) fn test_frames(response: &[u8]) -> Vec<Frame> {
+    use std::io::Read;
+
+    // The greeting needs to be received separately from the response
+    let io = b"OK MPD 0.23.5\n".chain(response);
+    let mut connection = mpd_protocol::Connection::connect(io).unwrap();
+    let response = connection.receive().unwrap().unwrap();
+    response.into_iter().map_while(Result::ok).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
 
     use super::*;
+    use crate::commands::SaveMode;
 
     #[test]
     fn duration_parsing() {
@@ -479,6 +1091,280 @@ mod tests {
         assert_matches!(parse_duration("duration", "-1"), Err(_));
     }
 
+    #[test]
+    fn float_parsing() {
+        assert_eq!(
+            f32::from_value(String::from("-17.5"), "mixrampdb").unwrap(),
+            -17.5
+        );
+        assert_matches!(f32::from_value(String::from("asdf"), "mixrampdb"), Err(_));
+    }
+
+    fn parse_status(fields: &[(&str, &str)]) -> Result<Status, TypedResponseError> {
+        Status::from_fields(fields.iter().map(|&(k, v)| (k, String::from(v))))
+    }
+
+    #[test]
+    fn status_playing() {
+        // As sent by MPD 0.23
+        let status = parse_status(&[
+            ("volume", "100"),
+            ("repeat", "0"),
+            ("random", "1"),
+            ("single", "0"),
+            ("consume", "oneshot"),
+            ("partition", "default"),
+            ("playlist", "4"),
+            ("playlistlength", "12"),
+            ("mixrampdb", "0"),
+            ("state", "play"),
+            ("song", "3"),
+            ("songid", "4"),
+            ("time", "83:247"),
+            ("elapsed", "83.115"),
+            ("bitrate", "320"),
+            ("duration", "246.987"),
+            ("audio", "44100:24:2"),
+            ("nextsong", "4"),
+            ("nextsongid", "5"),
+        ])
+        .unwrap();
+
+        assert_eq!(status.volume, Some(100));
+        assert_eq!(status.state, PlayState::Playing);
+        assert!(status.random);
+        assert_eq!(status.consume, ConsumeMode::Oneshot);
+        assert_eq!(status.single, SingleMode::Disabled);
+        assert_eq!(status.partition.as_deref(), Some("default"));
+        assert_eq!(status.playlist_length, 12);
+        assert_eq!(status.current_song, Some((SongPosition(3), SongId(4))));
+        assert_eq!(status.next_song, Some((SongPosition(4), SongId(5))));
+        assert_eq!(status.elapsed, Some(Duration::from_millis(83_115)));
+        assert_eq!(status.duration, Some(Duration::from_millis(246_987)));
+        assert_eq!(status.bitrate, Some(320));
+        assert!(status.extra.is_empty());
+    }
+
+    #[test]
+    fn status_legacy_time() {
+        let fields = [
+            ("repeat", "0"),
+            ("random", "0"),
+            ("consume", "0"),
+            ("state", "pause"),
+        ];
+
+        for key in ["time", "Time"] {
+            let status = parse_status(&[&fields[..], &[(key, "83:247")]].concat()).unwrap();
+            assert_eq!(status.elapsed, Some(Duration::from_secs(83)));
+            assert_eq!(status.duration, Some(Duration::from_secs(247)));
+            assert!(status.extra.is_empty());
+
+            assert_matches!(
+                parse_status(&[&fields[..], &[(key, "83")]].concat()),
+                Err(_)
+            );
+        }
+    }
+
+    #[test]
+    fn status_extra_fields() {
+        let fields = [
+            ("repeat", "0"),
+            ("random", "0"),
+            ("consume", "0"),
+            ("state", "stop"),
+        ];
+
+        let status =
+            parse_status(&[&fields[..], &[("lastloadedplaylist", "foo")]].concat()).unwrap();
+        assert_eq!(status.extra.len(), 1);
+        assert_eq!(
+            status.extra.get("lastloadedplaylist").map(String::as_str),
+            Some("foo")
+        );
+        assert_eq!(status.volume, None);
+
+        let status = parse_status(&[&fields[..], &[("volume", "-1")]].concat()).unwrap();
+        assert_eq!(status.volume, None);
+        assert!(status.extra.is_empty());
+
+        let status = parse_status(&[&fields[..], &[("volume", "0")]].concat()).unwrap();
+        assert_eq!(status.volume, Some(0));
+    }
+
+    #[test]
+    fn status_elapsed_at() {
+        let fields = [
+            ("repeat", "0"),
+            ("random", "0"),
+            ("consume", "0"),
+            ("elapsed", "10.000"),
+            ("duration", "12.000"),
+        ];
+
+        let playing = parse_status(&[&fields[..], &[("state", "play")]].concat()).unwrap();
+        let at = |secs| playing.received_at + Duration::from_secs(secs);
+        assert_eq!(playing.elapsed_at(at(1)), Some(Duration::from_secs(11)));
+        assert_eq!(playing.elapsed_at(at(5)), Some(Duration::from_secs(12)));
+
+        let paused = parse_status(&[&fields[..], &[("state", "pause")]].concat()).unwrap();
+        assert_eq!(
+            paused.elapsed_at(paused.received_at + Duration::from_secs(1)),
+            Some(Duration::from_secs(10))
+        );
+
+        let mut later = playing.clone();
+        later.received_at = at(1);
+        assert_eq!(later, playing);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn status_elapsed_at_paused_clock() {
+        let status = parse_status(&[
+            ("repeat", "0"),
+            ("random", "0"),
+            ("consume", "0"),
+            ("state", "play"),
+            ("elapsed", "10.000"),
+        ])
+        .unwrap();
+
+        tokio::time::advance(Duration::from_secs(2)).await;
+
+        let now = tokio::time::Instant::now().into_std();
+        assert_eq!(status.elapsed_at(now), Some(Duration::from_secs(12)));
+    }
+
+    #[test]
+    fn status_diff() {
+        let fields = [
+            ("volume", "50"),
+            ("random", "0"),
+            ("consume", "0"),
+            ("state", "play"),
+        ];
+
+        let old = [
+            ("repeat", "0"),
+            ("song", "0"),
+            ("songid", "1"),
+            ("elapsed", "1.000"),
+        ];
+        let new = [
+            ("repeat", "1"),
+            ("song", "1"),
+            ("songid", "2"),
+            ("elapsed", "0.000"),
+        ];
+
+        let old = parse_status(&[&fields[..], &old].concat()).unwrap();
+        let new = parse_status(&[&fields[..], &new].concat()).unwrap();
+
+        assert!(Status::diff(&old, &old).is_empty());
+
+        let delta = Status::diff(&old, &new);
+        assert!(delta.song && delta.position && delta.options);
+        assert!(!delta.state && !delta.volume && !delta.queue);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive_from_frame() {
+        #[derive(Debug, PartialEq, FromFrame)]
+        struct Response {
+            volume: u8,
+            #[mpd(rename = "playlistlength")]
+            length: Option<usize>,
+            #[mpd(default)]
+            repeat: bool,
+            missing: Option<String>,
+        }
+
+        assert_eq!(
+            Response::from_frame(test_frames(b"volume: 50\nplaylistlength: 3\nOK\n").remove(0))
+                .unwrap(),
+            Response {
+                volume: 50,
+                length: Some(3),
+                repeat: false,
+                missing: None,
+            }
+        );
+
+        assert!(Response::from_frame(test_frames(b"playlistlength: 3\nOK\n").remove(0)).is_err());
+    }
+
+    #[test]
+    fn enum_round_trip() {
+        for state in [PlayState::Playing, PlayState::Paused, PlayState::Stopped] {
+            assert_eq!(state.to_string().parse(), Ok(state));
+        }
+
+        for mode in [
+            SingleMode::Enabled,
+            SingleMode::Disabled,
+            SingleMode::Oneshot,
+        ] {
+            assert_eq!(mode.to_string().parse(), Ok(mode));
+        }
+
+        for mode in [
+            ConsumeMode::Enabled,
+            ConsumeMode::Disabled,
+            ConsumeMode::Oneshot,
+        ] {
+            assert_eq!(mode.to_string().parse(), Ok(mode));
+        }
+
+        for mode in [SaveMode::Create, SaveMode::Append, SaveMode::Replace] {
+            assert_eq!(mode.to_string().parse(), Ok(mode));
+        }
+
+        let err = "playing".parse::<PlayState>().unwrap_err();
+        assert_eq!(err.value(), "playing");
+        assert_eq!(err.to_string(), r#"invalid play state "playing""#);
+    }
+
+    #[test]
+    fn image_mime_detection() {
+        assert_eq!(
+            detect_image_mime(b"\xFF\xD8\xFF\xE0\x00\x10JFIF"),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            detect_image_mime(b"\x89PNG\r\n\x1A\n\x00\x00"),
+            Some("image/png")
+        );
+        assert_eq!(
+            detect_image_mime(b"RIFF\x24\x00\x00\x00WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(detect_image_mime(b"GIF89a\x01\x00"), Some("image/gif"));
+        assert_eq!(detect_image_mime(b"RIFF\x24\x00\x00\x00WAVE"), None);
+        assert_eq!(detect_image_mime(b""), None);
+    }
+
+    #[test]
+    fn stats_db_update_time() {
+        let stats = Stats {
+            artists: 0,
+            albums: 0,
+            songs: 0,
+            uptime: Duration::ZERO,
+            playtime: Duration::ZERO,
+            db_playtime: Duration::ZERO,
+            db_last_update: 1_600_000_000,
+            extra: HashMap::new(),
+        };
+
+        assert_eq!(
+            stats.db_last_update_time(),
+            UNIX_EPOCH + Duration::from_secs(1_600_000_000)
+        );
+        assert!(stats.time_since_db_update().unwrap() > Duration::ZERO);
+    }
+
     #[test]
     fn channel_message_parsing() {
         assert_eq!(parse_channel_messages(Vec::new()).unwrap(), Vec::new());
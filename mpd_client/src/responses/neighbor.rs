@@ -0,0 +1,100 @@
+use mpd_protocol::response::Frame;
+
+use crate::responses::{FromFrame, TypedResponseError};
+
+/// A storage found on the network, as returned by [`listneighbors`].
+///
+/// [`listneighbors`]: crate::commands::definitions::ListNeighbors
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Neighbor {
+    /// URI of the storage, which can be passed to the `mount` command.
+    pub uri: String,
+    /// Human-readable name of the storage.
+    pub name: Option<String>,
+}
+
+impl Neighbor {
+    /// The scheme of the URI (e.g. `smb` or `nfs`), if it has one.
+    pub fn scheme(&self) -> Option<&str> {
+        self.uri.split_once("://").map(|(scheme, _)| scheme)
+    }
+
+    /// The name of the storage if available, falling back to the URI otherwise.
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.uri)
+    }
+
+    /// Returns `true` if this is an SMB/CIFS share.
+    pub fn is_smb(&self) -> bool {
+        self.scheme() == Some("smb")
+    }
+
+    /// Returns `true` if this is an NFS export.
+    pub fn is_nfs(&self) -> bool {
+        self.scheme() == Some("nfs")
+    }
+
+    /// Returns `true` if this is a UPnP media server.
+    pub fn is_upnp(&self) -> bool {
+        self.scheme() == Some("upnp")
+    }
+}
+
+impl FromFrame for Vec<Neighbor> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        parse_neighbors(frame)
+    }
+}
+
+fn parse_neighbors<I, K>(fields: I) -> Result<Vec<Neighbor>, TypedResponseError>
+where
+    I: IntoIterator<Item = (K, String)>,
+    K: AsRef<str>,
+{
+    let mut out: Vec<Neighbor> = Vec::new();
+
+    for (key, value) in fields {
+        match key.as_ref() {
+            "neighbor" => out.push(Neighbor {
+                uri: value,
+                name: None,
+            }),
+            "name" => match out.last_mut() {
+                Some(neighbor) if neighbor.name.is_none() => neighbor.name = Some(value),
+                _ => return Err(TypedResponseError::unexpected_field("neighbor", "name")),
+            },
+            other => return Err(TypedResponseError::unexpected_field("neighbor", other)),
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn neighbors_parsing() {
+        let neighbors = parse_neighbors(vec![
+            ("neighbor", String::from("smb://FOO")),
+            ("name", String::from("FOO (Samba 4.1.11-Debian)")),
+            (
+                "neighbor",
+                String::from("upnp://uuid:1234/urn:schemas-upnp-org:service:ContentDirectory:1"),
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(neighbors.len(), 2);
+        assert!(neighbors[0].is_smb());
+        assert_eq!(neighbors[0].display_name(), "FOO (Samba 4.1.11-Debian)");
+        assert!(neighbors[1].is_upnp());
+        assert_eq!(neighbors[1].display_name(), neighbors[1].uri);
+
+        assert_matches!(parse_neighbors(vec![("name", String::from("FOO"))]), Err(_));
+    }
+}
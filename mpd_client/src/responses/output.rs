@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use mpd_protocol::response::Frame;
+
+use crate::responses::{FromFieldValue, FromFrame, TypedResponseError};
+
+/// An audio output, as returned by [`outputs`].
+///
+/// [`outputs`]: crate::commands::definitions::Outputs
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Output {
+    /// ID of the output.
+    pub id: u32,
+    /// Name of the output.
+    pub name: String,
+    /// Name of the plugin used by the output, if reported by the server.
+    pub plugin: Option<String>,
+    /// Whether the output is enabled.
+    pub enabled: bool,
+    /// Runtime attributes of the output.
+    pub attributes: HashMap<String, String>,
+}
+
+impl FromFrame for Vec<Output> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        parse_outputs(frame)
+    }
+}
+
+fn parse_outputs<I, K>(fields: I) -> Result<Vec<Output>, TypedResponseError>
+where
+    I: IntoIterator<Item = (K, String)>,
+    K: AsRef<str>,
+{
+    let mut out: Vec<Output> = Vec::new();
+
+    for (key, value) in fields {
+        let key = key.as_ref();
+
+        if key == "outputid" {
+            out.push(Output {
+                id: u32::from_value(value, key)?,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let Some(output) = out.last_mut() else {
+            return Err(TypedResponseError::unexpected_field("outputid", key));
+        };
+
+        match key {
+            "outputname" => output.name = value,
+            "plugin" => output.plugin = Some(value),
+            "outputenabled" => output.enabled = bool::from_value(value, key)?,
+            "attribute" => {
+                let Some((name, attribute)) = value.split_once('=') else {
+                    return Err(TypedResponseError::invalid_value(key, value));
+                };
+
+                output
+                    .attributes
+                    .insert(name.to_owned(), attribute.to_owned());
+            }
+            other => return Err(TypedResponseError::unexpected_field("outputid", other)),
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn outputs_parsing() {
+        let outputs = parse_outputs(vec![
+            ("outputid", String::from("0")),
+            ("outputname", String::from("Speakers")),
+            ("plugin", String::from("alsa")),
+            ("outputenabled", String::from("1")),
+            ("attribute", String::from("allowed_formats=")),
+            ("attribute", String::from("dop=0")),
+            ("outputid", String::from("1")),
+            ("outputname", String::from("Stream")),
+            ("outputenabled", String::from("0")),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            outputs,
+            [
+                Output {
+                    id: 0,
+                    name: String::from("Speakers"),
+                    plugin: Some(String::from("alsa")),
+                    enabled: true,
+                    attributes: [
+                        (String::from("allowed_formats"), String::new()),
+                        (String::from("dop"), String::from("0")),
+                    ]
+                    .into(),
+                },
+                Output {
+                    id: 1,
+                    name: String::from("Stream"),
+                    plugin: None,
+                    enabled: false,
+                    attributes: HashMap::new(),
+                },
+            ]
+        );
+
+        assert_matches!(
+            parse_outputs(vec![("outputname", String::from("foo"))]),
+            Err(_)
+        );
+        assert_matches!(
+            parse_outputs(vec![
+                ("outputid", String::from("0")),
+                ("attribute", String::from("foo")),
+            ]),
+            Err(_)
+        );
+    }
+}
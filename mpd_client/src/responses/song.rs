@@ -4,7 +4,7 @@ use mpd_protocol::response::Frame;
 
 use crate::{
     commands::{SongId, SongPosition},
-    responses::{parse_duration, FromFieldValue, Timestamp, TypedResponseError},
+    responses::{parse_duration, AudioFormat, FromFieldValue, Timestamp, TypedResponseError},
     tag::Tag,
 };
 
@@ -57,6 +57,35 @@ impl SongInQueue {
 
         Ok(out)
     }
+
+    /// Convert the given fields into a list of `SongInQueue`s, skipping invalid fields.
+    pub(crate) fn from_fields_lenient<I, K>(
+        fields: I,
+    ) -> (Vec<SongInQueue>, Vec<TypedResponseError>)
+    where
+        I: IntoIterator<Item = (K, String)>,
+        K: AsRef<str>,
+    {
+        let mut out = Vec::new();
+        let mut errors = Vec::new();
+        let mut builder = SongBuilder::default();
+
+        for (key, value) in fields {
+            // The builder is left unchanged when a field fails to convert, so parsing can simply
+            // continue with the next field
+            match builder.field(key.as_ref(), value) {
+                Ok(Some(song)) => out.push(song),
+                Ok(None) => (),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if let Some(song) = builder.finish() {
+            out.push(song);
+        }
+
+        (out, errors)
+    }
 }
 
 /// A single song, as returned by the [playlist] or [current song] commands.
@@ -73,12 +102,21 @@ pub struct Song {
     pub url: String,
     /// The `duration` as returned by MPD.
     pub duration: Option<Duration>,
+    /// The part of the underlying file this song consists of.
+    ///
+    /// This is set for virtual tracks, such as the individual tracks of a CUE sheet, where `url`
+    /// refers to a path below the file containing the actual audio data.
+    pub range: Option<SongRange>,
     /// Tags in this response.
     pub tags: HashMap<Tag, Vec<String>>,
-    /// The `format` as returned by MPD.
+    /// The `format` as returned by MPD. See [`Song::audio_format`] for a parsed version.
     pub format: Option<String>,
     /// Last modification date of the underlying file.
     pub last_modified: Option<Timestamp>,
+    /// Date the song was added to the database.
+    ///
+    /// **NOTE**: Reported by protocol versions 0.24 and later.
+    pub added: Option<Timestamp>,
 }
 
 impl Song {
@@ -88,6 +126,56 @@ impl Song {
         Path::new(&self.url)
     }
 
+    /// Returns `true` if the song is a remote stream (e.g. an `http://` URL) rather than a file.
+    ///
+    /// Local files outside the database, which are referred to using `file://` URIs, are not
+    /// considered streams.
+    pub fn is_stream(&self) -> bool {
+        uri_scheme(&self.url).is_some_and(|scheme| !scheme.eq_ignore_ascii_case("file"))
+    }
+
+    /// Get the last component of the song's path.
+    ///
+    /// For URIs, this ignores the host as well as any query or fragment. Returns `None` if the
+    /// path is empty or ends with a slash (e.g. `http://radio.example.com/`).
+    pub fn file_name(&self) -> Option<&str> {
+        let path = uri_path(&self.url);
+        let name = path.rsplit_once('/').map_or(path, |(_, name)| name);
+
+        if name.is_empty() {
+            None
+        } else {
+            Some(name)
+        }
+    }
+
+    /// Get the directory of the song in the database.
+    ///
+    /// Songs in the root of the database are in the empty directory. Returns `None` for streams
+    /// and files outside the database, which are referred to using URIs.
+    pub fn directory(&self) -> Option<&str> {
+        if uri_scheme(&self.url).is_some() {
+            return None;
+        }
+
+        Some(self.url.rsplit_once('/').map_or("", |(dir, _)| dir))
+    }
+
+    /// Get the extension of the song's file name, without the leading dot.
+    ///
+    /// Returns `None` if the file name has no extension, or only starts with a dot.
+    pub fn extension(&self) -> Option<&str> {
+        match self.file_name()?.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() && !extension.is_empty() => Some(extension),
+            _ => None,
+        }
+    }
+
+    /// Get the audio format of the song, if it is known and valid.
+    pub fn audio_format(&self) -> Option<AudioFormat> {
+        self.format.as_deref().and_then(AudioFormat::parse)
+    }
+
     /// Get all artists of the song.
     pub fn artists(&self) -> &[String] {
         self.tag_values(&Tag::Artist)
@@ -112,22 +200,73 @@ impl Song {
     ///
     /// If either are not set on the song, 0 is returned. This is a utility for sorting.
     pub fn number(&self) -> (u64, u64) {
-        let disc = self.single_tag_value(&Tag::Disc);
-        let track = self.single_tag_value(&Tag::Track);
+        let disc = self.disc().map_or(0, |(n, _)| n);
+        let track = self.track().map_or(0, |(n, _)| n);
+
+        (disc, track)
+    }
+
+    /// Get the track number of the song, and the total number of tracks if present.
+    ///
+    /// This parses values in both the `3` and `3/12` forms.
+    pub fn track(&self) -> Option<(u64, Option<u64>)> {
+        self.single_tag_value(&Tag::Track)
+            .and_then(parse_number_and_total)
+    }
+
+    /// Get the disc number of the song, and the total number of discs if present.
+    ///
+    /// This parses values in both the `1` and `1/2` forms.
+    pub fn disc(&self) -> Option<(u64, Option<u64>)> {
+        self.single_tag_value(&Tag::Disc)
+            .and_then(parse_number_and_total)
+    }
 
-        (
-            disc.and_then(|v| v.parse().ok()).unwrap_or(0),
-            track.and_then(|v| v.parse().ok()).unwrap_or(0),
-        )
+    /// Get the MusicBrainz recording ID of the song.
+    pub fn musicbrainz_recording_id(&self) -> Option<&str> {
+        self.single_tag_value(&Tag::MusicBrainzRecordingId)
+    }
+
+    /// Get the MusicBrainz track ID of the song, identifying the track on a specific release.
+    pub fn musicbrainz_track_id(&self) -> Option<&str> {
+        self.single_tag_value(&Tag::MusicBrainzTrackId)
+    }
+
+    /// Get the MusicBrainz release (album) ID of the song.
+    pub fn musicbrainz_release_id(&self) -> Option<&str> {
+        self.single_tag_value(&Tag::MusicBrainzReleaseId)
+    }
+
+    /// Get the MusicBrainz work ID of the song.
+    pub fn musicbrainz_work_id(&self) -> Option<&str> {
+        self.single_tag_value(&Tag::MusicBrainzWorkId)
+    }
+
+    /// Get all MusicBrainz artist IDs of the song.
+    pub fn musicbrainz_artist_ids(&self) -> &[String] {
+        self.tag_values(&Tag::MusicBrainzArtistId)
+    }
+
+    /// Get all MusicBrainz release artist (album artist) IDs of the song.
+    pub fn musicbrainz_release_artist_ids(&self) -> &[String] {
+        self.tag_values(&Tag::MusicBrainzReleaseArtistId)
     }
 
     /// Convert the given frame into a list of `Song`s.
     pub(crate) fn from_frame_multi(frame: Frame) -> Result<Vec<Song>, TypedResponseError> {
+        Song::from_fields(frame)
+    }
+
+    fn from_fields<I, K>(fields: I) -> Result<Vec<Song>, TypedResponseError>
+    where
+        I: IntoIterator<Item = (K, String)>,
+        K: AsRef<str>,
+    {
         let mut out = Vec::new();
         let mut builder = SongBuilder::default();
 
-        for (key, value) in frame {
-            if let Some(SongInQueue { song, .. }) = builder.field(&key, value)? {
+        for (key, value) in fields {
+            if let Some(SongInQueue { song, .. }) = builder.field(key.as_ref(), value)? {
                 out.push(song);
             }
         }
@@ -139,7 +278,22 @@ impl Song {
         Ok(out)
     }
 
-    fn tag_values(&self, tag: &Tag) -> &[String] {
+    /// Normalize the tag values of the song according to the given options.
+    ///
+    /// Values which are empty after normalization are removed, as are tags without any remaining
+    /// values.
+    pub fn normalize_tags(&mut self, options: &TagNormalization) {
+        self.tags.retain(|tag, values| {
+            if options.applies_to(tag) {
+                *values = options.normalize(mem::take(values));
+            }
+
+            !values.is_empty()
+        });
+    }
+
+    /// Get all values of the given tag, in the order they were returned by the server.
+    pub fn tag_values(&self, tag: &Tag) -> &[String] {
         match self.tags.get(tag) {
             Some(v) => v.as_slice(),
             None => &[],
@@ -154,6 +308,108 @@ impl Song {
     }
 }
 
+/// Options for normalizing tag values using [`Song::normalize_tags`].
+///
+/// By default, no normalization is performed.
+///
+/// ```
+/// use mpd_client::{responses::TagNormalization, tag::Tag};
+///
+/// let options = TagNormalization::new()
+///     .trim()
+///     .split_on(';')
+///     .split_on('\0')
+///     .dedupe()
+///     .only([Tag::Artist, Tag::AlbumArtist]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TagNormalization {
+    trim: bool,
+    separators: Vec<char>,
+    dedupe: bool,
+    tags: Option<Vec<Tag>>,
+}
+
+impl TagNormalization {
+    /// Create options which don't perform any normalization.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove leading and trailing whitespace from values.
+    pub fn trim(self) -> Self {
+        Self { trim: true, ..self }
+    }
+
+    /// Split values containing the given separator into multiple values.
+    ///
+    /// This may be called multiple times to split on several separators.
+    pub fn split_on(mut self, separator: char) -> Self {
+        self.separators.push(separator);
+        self
+    }
+
+    /// Remove duplicate values of the same tag, keeping the first occurrence.
+    pub fn dedupe(self) -> Self {
+        Self {
+            dedupe: true,
+            ..self
+        }
+    }
+
+    /// Only normalize the given tags, instead of all of them.
+    ///
+    /// This is useful when splitting on separators which commonly appear in e.g. titles.
+    pub fn only<I>(self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = Tag>,
+    {
+        Self {
+            tags: Some(tags.into_iter().collect()),
+            ..self
+        }
+    }
+
+    fn applies_to(&self, tag: &Tag) -> bool {
+        match &self.tags {
+            Some(tags) => tags.contains(tag),
+            None => true,
+        }
+    }
+
+    fn normalize(&self, values: Vec<String>) -> Vec<String> {
+        let mut out: Vec<String> = Vec::with_capacity(values.len());
+
+        for value in values {
+            let parts: Vec<&str> = if self.separators.is_empty() {
+                vec![&value]
+            } else {
+                value.split(self.separators.as_slice()).collect()
+            };
+
+            for part in parts {
+                let part = if self.trim { part.trim() } else { part };
+
+                if part.is_empty() || (self.dedupe && out.iter().any(|v| v == part)) {
+                    continue;
+                }
+
+                out.push(part.to_owned());
+            }
+        }
+
+        out
+    }
+}
+
+/// Parse a number with an optional total, like `3/12`.
+fn parse_number_and_total(raw: &str) -> Option<(u64, Option<u64>)> {
+    match raw.split_once('/') {
+        Some((number, total)) => Some((number.trim().parse().ok()?, total.trim().parse().ok())),
+        None => Some((raw.trim().parse().ok()?, None)),
+    }
+}
+
 /// Range used when playing only part of a [`Song`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SongRange {
@@ -183,7 +439,7 @@ impl FromFieldValue for SongRange {
 }
 
 #[derive(Debug, Default)]
-struct SongBuilder {
+pub(super) struct SongBuilder {
     url: String,
     position: usize,
     id: u64,
@@ -193,13 +449,14 @@ struct SongBuilder {
     tags: HashMap<Tag, Vec<String>>,
     format: Option<String>,
     last_modified: Option<Timestamp>,
+    added: Option<Timestamp>,
 }
 
 impl SongBuilder {
     /// Handle a field from a song list.
     ///
     /// If this returns `Ok(Some(_))`, a song was completed and another one started.
-    fn field(
+    pub(super) fn field(
         &mut self,
         key: &str,
         value: String,
@@ -263,6 +520,7 @@ impl SongBuilder {
                 let lm = Timestamp::from_value(value, "Last-Modified")?;
                 self.last_modified = Some(lm);
             }
+            "Added" => self.added = Some(Timestamp::from_value(value, "Added")?),
             "Prio" => self.priority = u8::from_value(value, "Prio")?,
             "Pos" => self.position = usize::from_value(value, "Pos")?,
             "Id" => self.id = u64::from_value(value, "Id")?,
@@ -279,7 +537,7 @@ impl SongBuilder {
     }
 
     /// Finish the building process. This returns the final song, if there is one.
-    fn finish(self) -> Option<SongInQueue> {
+    pub(super) fn finish(self) -> Option<SongInQueue> {
         if self.url.is_empty() {
             None
         } else {
@@ -298,14 +556,44 @@ impl SongBuilder {
             song: Song {
                 url: self.url,
                 duration: self.duration,
+                range: self.range,
                 tags: self.tags,
                 format: self.format,
                 last_modified: self.last_modified,
+                added: self.added,
             },
         }
     }
 }
 
+/// Returns the scheme of the given song URI, if it is a URI and not a path in the database.
+fn uri_scheme(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once("://")?;
+
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    valid.then_some(scheme)
+}
+
+/// Returns the path component of the given song URI.
+///
+/// Paths in the database are returned unchanged, since they may contain characters like `?` or
+/// `#` that would have special meaning in a URI.
+fn uri_path(url: &str) -> &str {
+    let Some(scheme) = uri_scheme(url) else {
+        return url;
+    };
+
+    // Skip the authority (host, port and user info)
+    let rest = &url[scheme.len() + 3..];
+    let path = rest.find('/').map_or("", |start| &rest[start..]);
+
+    path.split(['?', '#']).next().unwrap_or_default()
+}
+
 /// Returns `true` if the given field name starts a new song entry.
 fn is_start_field(f: &str) -> bool {
     matches!(f, "file" | "directory" | "playlist")
@@ -316,9 +604,120 @@ mod tests {
     use assert_matches::assert_matches;
 
     use super::*;
+    use crate::responses::SampleFormat;
 
     const TEST_TIMESTAMP: &str = "2020-06-12T17:53:00Z";
 
+    #[test]
+    fn audio_format() {
+        let mut builder = SongBuilder::default();
+        builder.field("file", String::from("test.flac")).unwrap();
+        builder.field("Format", String::from("96000:24:2")).unwrap();
+
+        let format = builder.finish().unwrap().song.audio_format().unwrap();
+        assert_eq!(format.sample_rate, Some(96000));
+        assert_eq!(format.bits, Some(SampleFormat::Bits(24)));
+        assert_eq!(format.channels, Some(2));
+    }
+
+    #[test]
+    fn lenient_parsing() {
+        let (songs, errors) = SongInQueue::from_fields_lenient([
+            ("file", String::from("foo.flac")),
+            ("duration", String::from("asdf")),
+            ("Title", String::from("Foo")),
+            ("file", String::from("bar.flac")),
+            ("duration", String::from("1.5")),
+        ]);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(songs.len(), 2);
+        assert_eq!(songs[0].song.duration, None);
+        assert_eq!(songs[0].song.title(), Some("Foo"));
+        assert_eq!(songs[1].song.duration, Some(Duration::from_secs_f64(1.5)));
+    }
+
+    #[test]
+    fn musicbrainz_accessors() {
+        let mut builder = SongBuilder::default();
+
+        for (key, value) in [
+            ("file", "test.flac"),
+            ("MUSICBRAINZ_TRACKID", "recording"),
+            ("MUSICBRAINZ_RELEASETRACKID", "track"),
+            ("MUSICBRAINZ_ALBUMID", "release"),
+            ("MUSICBRAINZ_ARTISTID", "artist 1"),
+            ("MUSICBRAINZ_ARTISTID", "artist 2"),
+        ] {
+            builder.field(key, String::from(value)).unwrap();
+        }
+
+        let song = builder.finish().unwrap().song;
+
+        assert_eq!(song.musicbrainz_recording_id(), Some("recording"));
+        assert_eq!(song.musicbrainz_track_id(), Some("track"));
+        assert_eq!(song.musicbrainz_release_id(), Some("release"));
+        assert_eq!(song.musicbrainz_work_id(), None);
+        assert_eq!(song.musicbrainz_artist_ids(), ["artist 1", "artist 2"]);
+        assert_eq!(
+            song.tag_values(&Tag::MusicBrainzArtistId),
+            ["artist 1", "artist 2"]
+        );
+        assert!(song.musicbrainz_release_artist_ids().is_empty());
+    }
+
+    #[test]
+    fn track_and_disc_numbers() {
+        let songs = Song::from_fields([
+            ("file", String::from("a.flac")),
+            ("Track", String::from("3/12")),
+            ("Disc", String::from("2")),
+            ("file", String::from("b.flac")),
+            ("Track", String::from("A1")),
+        ])
+        .unwrap();
+
+        assert_eq!(songs[0].track(), Some((3, Some(12))));
+        assert_eq!(songs[0].disc(), Some((2, None)));
+        assert_eq!(songs[0].number(), (2, 3));
+
+        assert_eq!(songs[1].track(), None);
+        assert_eq!(songs[1].disc(), None);
+        assert_eq!(songs[1].number(), (0, 0));
+    }
+
+    #[test]
+    fn tag_normalization() {
+        let mut song = Song::from_fields([
+            ("file", String::from("a.flac")),
+            ("Artist", String::from(" Foo ; Bar")),
+            ("Artist", String::from("Foo")),
+            ("Artist", String::from("Baz\0")),
+            ("Title", String::from("Either; Or ")),
+        ])
+        .unwrap()
+        .remove(0);
+
+        let unchanged = song.clone();
+        song.normalize_tags(&TagNormalization::new());
+        assert_eq!(song, unchanged);
+
+        song.normalize_tags(
+            &TagNormalization::new()
+                .trim()
+                .split_on(';')
+                .split_on('\0')
+                .dedupe()
+                .only([Tag::Artist]),
+        );
+
+        assert_eq!(song.artists(), ["Foo", "Bar", "Baz"]);
+        assert_eq!(song.title(), Some("Either; Or "));
+
+        song.normalize_tags(&TagNormalization::new().trim());
+        assert_eq!(song.title(), Some("Either; Or"));
+    }
+
     #[test]
     fn song_builder() {
         let mut builder = SongBuilder::default();
@@ -329,6 +728,10 @@ mod tests {
             builder.field("Last-Modified", String::from(TEST_TIMESTAMP)),
             Ok(None)
         );
+        assert_matches!(
+            builder.field("Added", String::from(TEST_TIMESTAMP)),
+            Ok(None)
+        );
         assert_matches!(builder.field("Title", String::from("Foo")), Ok(None));
         assert_matches!(builder.field("Id", String::from("12")), Ok(None));
         assert_matches!(builder.field("Pos", String::from("5")), Ok(None));
@@ -348,8 +751,10 @@ mod tests {
                 song: Song {
                     url: String::from("test.flac"),
                     duration: Some(Duration::from_secs_f64(123.456)),
+                    range: None,
                     format: None,
                     last_modified: Some(Timestamp::from_value(TEST_TIMESTAMP.into(), "").unwrap()),
+                    added: Some(Timestamp::from_value(TEST_TIMESTAMP.into(), "").unwrap()),
                     tags: [(Tag::Title, vec![String::from("Foo")])].into(),
                 }
             }
@@ -367,8 +772,10 @@ mod tests {
                 song: Song {
                     url: String::from("foo.flac"),
                     duration: None,
+                    range: None,
                     format: None,
                     last_modified: None,
+                    added: None,
                     tags: HashMap::new(),
                 }
             }
@@ -402,8 +809,10 @@ mod tests {
                 song: Song {
                     url: String::from("foo.flac"),
                     duration: None,
+                    range: None,
                     format: None,
                     last_modified: None,
+                    added: None,
                     tags: HashMap::new(),
                 }
             }
@@ -435,12 +844,74 @@ mod tests {
                 url: String::from("foo.flac"),
                 format: None,
                 last_modified: None,
+                added: None,
                 duration: Some(Duration::from_secs_f64(456.7)),
+                range: None,
                 tags: HashMap::new(),
             }
         );
     }
 
+    #[test]
+    fn uri_helpers() {
+        let song = |url: &str| Song {
+            url: url.into(),
+            duration: None,
+            range: None,
+            tags: HashMap::new(),
+            format: None,
+            last_modified: None,
+            added: None,
+        };
+
+        let file = song("Artist/Album #1/01 What?.flac");
+        assert!(!file.is_stream());
+        assert_eq!(file.file_name(), Some("01 What?.flac"));
+        assert_eq!(file.directory(), Some("Artist/Album #1"));
+        assert_eq!(file.extension(), Some("flac"));
+
+        let root = song(".hidden");
+        assert_eq!(root.directory(), Some(""));
+        assert_eq!(root.extension(), None);
+
+        let stream = song("https://radio.example.com/live/stream.mp3?token=a/b#x");
+        assert!(stream.is_stream());
+        assert_eq!(stream.file_name(), Some("stream.mp3"));
+        assert_eq!(stream.directory(), None);
+        assert_eq!(stream.extension(), Some("mp3"));
+
+        let bare = song("http://radio.example.com");
+        assert_eq!(bare.file_name(), None);
+        assert_eq!(bare.extension(), None);
+
+        let local = song("file:///home/user/song.ogg");
+        assert!(!local.is_stream());
+        assert_eq!(local.file_name(), Some("song.ogg"));
+        assert_eq!(local.directory(), None);
+    }
+
+    #[test]
+    fn cue_track() {
+        let songs = Song::from_fields([
+            ("file", String::from("album.cue/track0002")),
+            ("Range", String::from("213.000-420.500")),
+            ("Title", String::from("Second")),
+            ("file", String::from("album.cue/track0003")),
+            ("Range", String::from("420.500-")),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            songs[0].range,
+            Some(SongRange {
+                from: Duration::from_secs(213),
+                to: Some(Duration::from_secs_f64(420.5)),
+            })
+        );
+        assert_eq!(songs[0].title(), Some("Second"));
+        assert_eq!(songs[1].range.unwrap().to, None);
+    }
+
     #[test]
     fn parse_range() {
         assert_eq!(
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, error::Error, str::FromStr};
 
 use mpd_protocol::response::Frame;
 
-use crate::responses::{KeyValuePair, TypedResponseError};
+use crate::responses::{FromFrame, TypedResponseError};
 
 /// Response to the [`sticker get`] command.
 ///
@@ -14,8 +14,8 @@ pub struct StickerGet {
     pub value: String,
 }
 
-impl StickerGet {
-    pub(crate) fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+impl FromFrame for StickerGet {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
         let Some((key, field_value)) = frame.into_iter().next() else {
             return Err(TypedResponseError::missing("sticker"));
         };
@@ -51,10 +51,8 @@ pub struct StickerList {
     pub value: HashMap<String, String>,
 }
 
-impl StickerList {
-    pub(crate) fn from_frame(
-        raw: impl IntoIterator<Item = KeyValuePair>,
-    ) -> Result<Self, TypedResponseError> {
+impl FromFrame for StickerList {
+    fn from_frame(raw: Frame) -> Result<Self, TypedResponseError> {
         let value = raw
             .into_iter()
             .map(|(_, value)| parse_sticker_value(value))
@@ -76,30 +74,101 @@ impl From<StickerList> for HashMap<String, String> {
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct StickerFind {
-    /// A map of songs to their sticker values
-    pub value: HashMap<String, String>,
+    /// The found stickers, in the order they were returned
+    pub stickers: Vec<Sticker>,
 }
 
 impl StickerFind {
-    pub(crate) fn from_frame(
-        raw: impl IntoIterator<Item = KeyValuePair>,
-    ) -> Result<Self, TypedResponseError> {
-        let mut value = HashMap::new();
-
-        let mut file = String::new();
-
-        for (key, tag) in raw {
-            match &*key {
-                "file" => file = tag,
+    fn from_fields<I, K>(fields: I) -> Result<Self, TypedResponseError>
+    where
+        I: IntoIterator<Item = (K, String)>,
+        K: AsRef<str>,
+    {
+        let mut stickers = Vec::new();
+        let mut uri = None;
+
+        for (key, value) in fields {
+            match key.as_ref() {
+                "file" => uri = Some(value),
                 "sticker" => {
-                    let (_, sticker_value) = parse_sticker_value(tag)?;
-                    value.insert(file.clone(), sticker_value);
+                    let Some(uri) = uri.take() else {
+                        return Err(TypedResponseError::unexpected_field("file", "sticker"));
+                    };
+
+                    let (name, value) = parse_sticker_value(value)?;
+                    stickers.push(Sticker { uri, name, value });
                 }
                 other => return Err(TypedResponseError::unexpected_field("sticker", other)),
             }
         }
 
-        Ok(Self { value })
+        Ok(Self { stickers })
+    }
+}
+
+impl FromFrame for StickerFind {
+    fn from_frame(raw: Frame) -> Result<Self, TypedResponseError> {
+        Self::from_fields(raw)
+    }
+}
+
+impl From<StickerFind> for Vec<Sticker> {
+    fn from(sticker_find: StickerFind) -> Self {
+        sticker_find.stickers
+    }
+}
+
+/// A sticker on a song, as returned by the [`sticker find`] command.
+///
+/// The value is a `String`, unless it was parsed using [`Sticker::parse`] or
+/// [`StickerFind::parse_as`].
+///
+/// [`sticker find`]: crate::commands::definitions::StickerFind
+/// [`StickerFind::parse_as`]: crate::commands::definitions::StickerFind::parse_as
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Sticker<T = String> {
+    /// URI of the song the sticker is on
+    pub uri: String,
+    /// Name of the sticker
+    pub name: String,
+    /// The sticker value
+    pub value: T,
+}
+
+impl Sticker {
+    /// Parse the sticker value into `T`.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the value fails to parse.
+    pub fn parse_value<T>(&self) -> Result<T, TypedResponseError>
+    where
+        T: FromStr,
+        T::Err: Error + Send + Sync + 'static,
+    {
+        self.value
+            .parse()
+            .map_err(|e| TypedResponseError::invalid_value("sticker", self.value.clone()).source(e))
+    }
+
+    /// Parse the sticker value into `T`, keeping the URI and name.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the value fails to parse.
+    pub fn parse<T>(self) -> Result<Sticker<T>, TypedResponseError>
+    where
+        T: FromStr,
+        T::Err: Error + Send + Sync + 'static,
+    {
+        let value = self.parse_value()?;
+
+        Ok(Sticker {
+            uri: self.uri,
+            name: self.name,
+            value,
+        })
     }
 }
 
@@ -114,3 +183,50 @@ fn parse_sticker_value(mut tag: String) -> Result<(String, String), TypedRespons
         None => Err(TypedResponseError::invalid_value("sticker", tag)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn sticker_find() {
+        let found = StickerFind::from_fields([
+            ("file", String::from("a.flac")),
+            ("sticker", String::from("rating=4")),
+            ("file", String::from("b.flac")),
+            ("sticker", String::from("rating=x=y")),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            found.stickers,
+            [
+                Sticker {
+                    uri: String::from("a.flac"),
+                    name: String::from("rating"),
+                    value: String::from("4"),
+                },
+                Sticker {
+                    uri: String::from("b.flac"),
+                    name: String::from("rating"),
+                    value: String::from("x=y"),
+                },
+            ]
+        );
+
+        assert_eq!(found.stickers[0].parse_value::<u8>().unwrap(), 4);
+        assert_matches!(found.stickers[1].parse_value::<u8>(), Err(_));
+
+        let parsed = found.stickers[0].clone().parse::<u8>().unwrap();
+        assert_eq!(parsed.uri, "a.flac");
+        assert_eq!(parsed.name, "rating");
+        assert_eq!(parsed.value, 4);
+
+        assert_matches!(
+            StickerFind::from_fields([("sticker", String::from("rating=4"))]),
+            Err(_)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use mpd_protocol::response::Frame;
+use tracing::trace;
+
+use crate::{
+    client::{Client, CommandError, ConnectionEvents},
+    commands,
+    responses::{value, FromFrame, TypedResponseError},
+};
+
+/// Number of distinct update job IDs, after which MPD wraps around to the first one.
+const JOB_ID_RANGE: u64 = 1 << 15;
+
+/// Handle to a database update job, as started by the [`Update`] and [`Rescan`] commands.
+///
+/// [`Update`]: crate::commands::Update
+/// [`Rescan`]: crate::commands::Rescan
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UpdateJob(u64);
+
+impl UpdateJob {
+    /// Create a handle for the job with the given ID.
+    pub fn new(id: u64) -> UpdateJob {
+        UpdateJob(id)
+    }
+
+    /// Returns the ID of the job.
+    pub fn id(self) -> u64 {
+        self.0
+    }
+
+    /// Wait for the job to finish.
+    ///
+    /// This checks the [`update_job`] of the status whenever a `database` or `update` event is
+    /// received on the given `events`. Since MPD runs update jobs in order, the job is finished
+    /// once no job or a later job is running.
+    ///
+    /// Events received on `events` while waiting are not lost, they are returned by
+    /// [`ConnectionEvents::next`] afterwards.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`], and if the connection
+    /// is closed before the job is finished.
+    ///
+    /// [`update_job`]: crate::responses::Status::update_job
+    pub async fn finished(
+        self,
+        client: &Client,
+        events: &mut ConnectionEvents,
+    ) -> Result<(), CommandError> {
+        loop {
+            let running = client.command(commands::Status).await?.update_job;
+
+            if self.is_finished(running) {
+                trace!(job = self.0, "database update finished");
+                return Ok(());
+            }
+
+            events.next_update_event().await?;
+        }
+    }
+
+    /// Returns `true` if this job is finished, given the ID of the currently running job.
+    ///
+    /// MPD runs jobs in the order of their IDs, so this job is finished if no job or a later job
+    /// is running. Since the IDs wrap around, a running job is considered later if it is less
+    /// than half the ID range ahead of this one.
+    pub(crate) fn is_finished(self, running: Option<u64>) -> bool {
+        let Some(running) = running else {
+            return true;
+        };
+
+        let ahead = running.wrapping_sub(self.0) % JOB_ID_RANGE;
+        ahead != 0 && ahead < JOB_ID_RANGE / 2
+    }
+}
+
+impl From<UpdateJob> for u64 {
+    fn from(job: UpdateJob) -> Self {
+        job.0
+    }
+}
+
+impl FromFrame for UpdateJob {
+    fn from_frame(mut frame: Frame) -> Result<UpdateJob, TypedResponseError> {
+        value(&mut frame, "updating_db").map(UpdateJob)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_finished() {
+        let job = UpdateJob::new(5);
+
+        assert!(job.is_finished(None));
+        assert!(job.is_finished(Some(6)));
+        assert!(!job.is_finished(Some(5)));
+        assert!(!job.is_finished(Some(4)));
+
+        // IDs wrap around to 1 after 32768
+        let job = UpdateJob::new(32767);
+
+        assert!(job.is_finished(Some(32768)));
+        assert!(job.is_finished(Some(1)));
+        assert!(!job.is_finished(Some(32766)));
+
+        let job = UpdateJob::new(1);
+
+        assert!(!job.is_finished(Some(32768)));
+        assert!(job.is_finished(Some(2)));
+    }
+}
@@ -5,6 +5,7 @@ use std::{
     error::Error,
     fmt,
     hash::{Hash, Hasher},
+    str::FromStr,
 };
 
 use bytes::{BufMut, BytesMut};
@@ -179,6 +180,22 @@ impl<'a> TryFrom<&'a str> for Tag {
     }
 }
 
+impl FromStr for Tag {
+    type Err = TagError;
+
+    /// Parse a tag, equivalent to the `TryFrom` implementation.
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Tag::try_from(raw)
+    }
+}
+
+/// Renders the tag as used in the protocol.
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.as_str())
+    }
+}
+
 impl PartialEq for Tag {
     fn eq(&self, other: &Tag) -> bool {
         self.as_str() == other.as_str()
@@ -259,6 +276,19 @@ mod tests {
         assert_eq!(Tag::try_from("foo"), Ok(Tag::Other(Box::from("foo"))));
     }
 
+    #[test]
+    fn round_trip() {
+        for tag in [
+            Tag::Album,
+            Tag::MusicBrainzTrackId,
+            Tag::Other("foo".into()),
+        ] {
+            assert_eq!(tag.to_string().parse(), Ok(tag));
+        }
+
+        assert_eq!("".parse::<Tag>(), Err(TagError::Empty));
+    }
+
     #[test]
     fn try_from_error() {
         assert_eq!(Tag::try_from(""), Err(TagError::Empty));
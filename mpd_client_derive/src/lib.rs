@@ -0,0 +1,113 @@
+#![warn(missing_docs, rust_2018_idioms, unreachable_pub)]
+#![forbid(unsafe_code)]
+
+//! Derive macros for [`mpd_client`](https://docs.rs/mpd_client).
+//!
+//! Use these through the `derive` feature of `mpd_client`, which reexports them.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, LitStr, PathArguments,
+    Type,
+};
+
+/// Derive `FromFrame` for a struct with named fields.
+///
+/// Every field is read from the response field with the same name, converted using
+/// `FromFieldValue`. Fields are required, unless their type is an `Option`.
+///
+/// The following attributes are supported on fields:
+///
+/// - `#[mpd(rename = "name")]`: Read the value from the response field with the given name.
+/// - `#[mpd(default)]`: Use the `Default` value if the field is missing.
+#[proc_macro_derive(FromFrame, attributes(mpd))]
+pub fn derive_from_frame(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match from_frame_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+fn from_frame_impl(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "FromFrame can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "FromFrame can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut conversions = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field without name");
+        let mut name = ident.to_string().trim_start_matches("r#").to_owned();
+        let mut default = false;
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("mpd")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    default = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported attribute, expected `rename` or `default`"))
+                }
+            })?;
+        }
+
+        let conversion = if default {
+            quote!(::mpd_client::responses::optional_value(&mut frame, #name)?.unwrap_or_default())
+        } else if is_option(&field.ty) {
+            quote!(::mpd_client::responses::optional_value(&mut frame, #name)?)
+        } else {
+            quote!(::mpd_client::responses::value(&mut frame, #name)?)
+        };
+
+        conversions.push(quote!(#ident: #conversion));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::mpd_client::responses::FromFrame for #name #ty_generics #where_clause {
+            fn from_frame(
+                mut frame: ::mpd_client::protocol::response::Frame,
+            ) -> ::std::result::Result<Self, ::mpd_client::responses::TypedResponseError> {
+                ::std::result::Result::Ok(Self {
+                    #(#conversions,)*
+                })
+            }
+        }
+    })
+}
+
+/// Returns `true` if the type is (syntactically) an `Option`.
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.qself.is_none()
+        && path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "Option"
+                && matches!(segment.arguments, PathArguments::AngleBracketed(_))
+        })
+}
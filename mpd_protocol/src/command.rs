@@ -16,9 +16,12 @@ use std::{
 
 use bytes::{BufMut, Bytes, BytesMut};
 
-/// Start a command list, separated with list terminators. Our parser can't separate messages when
-/// the form of command list without terminators is used.
-const COMMAND_LIST_BEGIN: &[u8] = b"command_list_ok_begin\n";
+/// Start a command list, separated with list terminators.
+const COMMAND_LIST_OK_BEGIN: &[u8] = b"command_list_ok_begin\n";
+
+/// Start a command list without list terminators. The responses to all commands are combined into a
+/// single frame.
+const COMMAND_LIST_BEGIN: &[u8] = b"command_list_begin\n";
 
 /// End a command list.
 const COMMAND_LIST_END: &[u8] = b"command_list_end\n";
@@ -99,8 +102,14 @@ impl Command {
 }
 
 /// A non-empty list of commands.
+///
+/// By default, the list is sent using `command_list_ok_begin`, so the response contains a separate
+/// frame for every command. See [`CommandList::combined`] for the alternative.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct CommandList(pub(crate) Vec<Command>);
+pub struct CommandList {
+    pub(crate) commands: Vec<Command>,
+    combined: bool,
+}
 
 #[allow(clippy::len_without_is_empty)]
 impl CommandList {
@@ -108,7 +117,28 @@ impl CommandList {
     ///
     /// Unless further commands are added, the command will not be wrapped into a list.
     pub fn new(first: Command) -> Self {
-        CommandList(vec![first])
+        CommandList {
+            commands: vec![first],
+            combined: false,
+        }
+    }
+
+    /// Send the list using `command_list_begin`, which makes the server return a single frame
+    /// containing the combined responses of all commands, instead of one frame per command.
+    ///
+    /// This avoids the per-command `list_OK` markers, which can be faster for large lists of
+    /// commands whose responses are not needed individually (e.g. many `add` commands). Note that
+    /// fields from the responses of different commands can not be told apart.
+    pub fn combined(mut self) -> Self {
+        self.combined = true;
+        self
+    }
+
+    /// Returns `true` if the list is sent using `command_list_begin`.
+    ///
+    /// See [`CommandList::combined`].
+    pub fn is_combined(&self) -> bool {
+        self.combined
     }
 
     /// Add another command to the list.
@@ -121,32 +151,38 @@ impl CommandList {
 
     /// Add another command to the list.
     pub fn add(&mut self, command: Command) {
-        self.0.push(command);
+        self.commands.push(command);
     }
 
     /// Get the number of commands in this command list.
     ///
     /// This is never 0.
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.commands.len()
     }
 
     pub(crate) fn render(mut self) -> BytesMut {
         if self.len() == 1 {
-            let mut buf = self.0.pop().unwrap().0;
+            let mut buf = self.commands.pop().unwrap().0;
             buf.put_u8(b'\n');
             return buf;
         }
 
+        let begin = if self.combined {
+            COMMAND_LIST_BEGIN
+        } else {
+            COMMAND_LIST_OK_BEGIN
+        };
+
         // Calculate required length
-        let required_length = COMMAND_LIST_BEGIN.len()
-            + self.0.iter().map(|c| c.0.len() + 1).sum::<usize>()
+        let required_length = begin.len()
+            + self.commands.iter().map(|c| c.0.len() + 1).sum::<usize>()
             + COMMAND_LIST_END.len();
 
         let mut buf = BytesMut::with_capacity(required_length);
 
-        buf.put_slice(COMMAND_LIST_BEGIN);
-        for command in self.0 {
+        buf.put_slice(begin);
+        for command in self.commands {
             buf.put_slice(&command.0);
             buf.put_u8(b'\n');
         }
@@ -158,7 +194,7 @@ impl CommandList {
 
 impl Extend<Command> for CommandList {
     fn extend<T: IntoIterator<Item = Command>>(&mut self, iter: T) {
-        self.0.extend(iter);
+        self.commands.extend(iter);
     }
 }
 
@@ -552,6 +552,26 @@ mod tests_sync {
         );
     }
 
+    #[test]
+    fn send_list_combined() {
+        let mut io = Vec::new();
+        let mut connection = new_conn(&mut io);
+
+        let list = CommandList::new(Command::new("foo"))
+            .command(Command::new("bar"))
+            .combined();
+
+        connection.send_list(list).unwrap();
+
+        assert_eq!(
+            io,
+            b"command_list_begin\n\
+              foo\n\
+              bar\n\
+              command_list_end\n"
+        );
+    }
+
     #[test]
     fn receive() {
         let io: &[u8] = b"foo: bar\nOK\n";
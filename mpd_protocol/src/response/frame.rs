@@ -94,6 +94,33 @@ impl Frame {
     pub fn take_binary(&mut self) -> Option<BytesMut> {
         self.binary.take()
     }
+
+    /// Serialize the frame into the MPD wire format.
+    ///
+    /// This contains the remaining key-value pairs, followed by the binary blob if there is one.
+    /// The terminating `OK` or `list_OK` line is not included, use
+    /// [`Response::to_protocol_bytes`][super::Response::to_protocol_bytes] to serialize complete
+    /// responses.
+    pub fn to_protocol_bytes(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+        self.write_protocol(&mut buf);
+        buf
+    }
+
+    pub(super) fn write_protocol(&self, buf: &mut BytesMut) {
+        for (key, value) in self.fields() {
+            buf.extend_from_slice(key.as_bytes());
+            buf.extend_from_slice(b": ");
+            buf.extend_from_slice(value.as_bytes());
+            buf.extend_from_slice(b"\n");
+        }
+
+        if let Some(binary) = &self.binary {
+            buf.extend_from_slice(format!("binary: {}\n", binary.len()).as_bytes());
+            buf.extend_from_slice(binary);
+            buf.extend_from_slice(b"\n");
+        }
+    }
 }
 
 impl fmt::Debug for Frame {
@@ -71,6 +71,35 @@ impl Response {
         self.into_iter().next().unwrap()
     }
 
+    /// Serialize the response into the MPD wire format.
+    ///
+    /// Parsing the result yields an identical response. Responses with more than one frame (or
+    /// with an error after successful frames) are serialized as responses to a command list
+    /// started with `command_list_ok_begin`.
+    pub fn to_protocol_bytes(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+
+        match (self.frames.as_slice(), &self.error) {
+            ([frame], None) => {
+                frame.write_protocol(&mut buf);
+                buf.extend_from_slice(b"OK\n");
+            }
+            (frames, error) => {
+                for frame in frames {
+                    frame.write_protocol(&mut buf);
+                    buf.extend_from_slice(b"list_OK\n");
+                }
+
+                match error {
+                    Some(error) => error.write_protocol(&mut buf),
+                    None => buf.extend_from_slice(b"OK\n"),
+                }
+            }
+        }
+
+        buf
+    }
+
     pub(crate) fn field_count(&self) -> usize {
         self.frames.iter().map(Frame::fields_len).sum()
     }
@@ -370,6 +399,19 @@ pub struct Error {
     pub message: Box<str>,
 }
 
+impl Error {
+    fn write_protocol(&self, buf: &mut BytesMut) {
+        let line = format!(
+            "ACK [{}@{}] {{{}}} {}\n",
+            self.code,
+            self.command_index,
+            self.current_command.as_deref().unwrap_or_default(),
+            self.message,
+        );
+        buf.extend_from_slice(line.as_bytes());
+    }
+}
+
 #[cfg(test)]
 mod test {
     use assert_matches::assert_matches;
@@ -666,6 +708,33 @@ mod test {
         assert_eq!(builder.state, ResponseState::Initial);
     }
 
+    #[test]
+    fn protocol_round_trip() {
+        let responses: [&[u8]; 6] = [
+            b"OK\n",
+            b"foo: bar\nfoo: baz\nOK\n",
+            b"size: 6\nbinary: 6\nOK\nOK\n\nOK\n",
+            b"foo: bar\nlist_OK\nlist_OK\nOK\n",
+            b"ACK [5@0] {} unknown command \"foo\"\n",
+            b"foo: bar\nlist_OK\nACK [2@1] {random} Boolean (0/1) expected: foo\n",
+        ];
+
+        for raw in responses {
+            let mut io = BytesMut::from(raw);
+            let mut field_cache = ResponseFieldCache::new();
+            let response = ResponseBuilder::new(&mut field_cache)
+                .parse(&mut io)
+                .expect("invalid")
+                .expect("incomplete");
+
+            assert_eq!(response.to_protocol_bytes(), raw);
+        }
+
+        let mut frame = frame([("foo", "bar"), ("hello", "world")], None);
+        frame.get("foo");
+        assert_eq!(frame.to_protocol_bytes(), "hello: world\n");
+    }
+
     #[test]
     fn key_interning() {
         let mut io = BytesMut::from("foo: bar\nfoo: baz\nOK\n");